@@ -0,0 +1,317 @@
+use std::{
+    fs,
+    hint::black_box,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+const WARM_UP_TIME: Duration = Duration::from_millis(100);
+const SAMPLE_TIME: Duration = Duration::from_millis(10);
+const SAMPLE_COUNT: usize = 30;
+const REGRESSION_THRESHOLD: f64 = 0.10;
+const BASELINE_DIR: &str = "target/aoc-bench";
+
+/// A hot function of a day, measured on generated inputs of each size to show how its running
+/// time grows.
+pub struct Benchmark {
+    pub name: &'static str,
+    pub sizes: &'static [usize],
+    /// Generates an input of the given size, then measures the function through the bencher.
+    pub run: fn(&mut Bencher, usize),
+}
+
+#[derive(Default)]
+pub struct Bencher {
+    /// Nanoseconds per iteration, one for each sample.
+    samples: Vec<f64>,
+}
+
+impl Bencher {
+    /// Measures a routine. After warming up, the routine is repeated enough times for each sample
+    /// to take a measurable amount of time, and the samples are averaged per iteration.
+    pub fn iter<T, F: FnMut() -> T>(&mut self, mut routine: F) {
+        let start = Instant::now();
+        let mut warm_up_iterations = 0_u64;
+
+        while start.elapsed() < WARM_UP_TIME {
+            black_box(routine());
+            warm_up_iterations += 1;
+        }
+
+        let iteration_time = start.elapsed().as_secs_f64() / warm_up_iterations as f64;
+        let iterations = ((SAMPLE_TIME.as_secs_f64() / iteration_time) as u64).max(1);
+
+        self.samples = (0..SAMPLE_COUNT)
+            .map(|_| {
+                let start = Instant::now();
+
+                for _ in 0..iterations {
+                    black_box(routine());
+                }
+
+                start.elapsed().as_secs_f64() * 1e9 / iterations as f64
+            })
+            .collect();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub median: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Estimate {
+    fn from_samples(samples: &[f64]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let count = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / count;
+        let variance = sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count;
+
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2.0
+        } else {
+            sorted[middle]
+        };
+
+        Self {
+            median,
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+
+    fn change_from(&self, previous: &Estimate) -> f64 {
+        self.median / previous.median - 1.0
+    }
+
+    /// Whether this is slower than a previous estimate by more than the threshold, and by more
+    /// than the noise of both measurements could explain.
+    fn regressed_from(&self, previous: &Estimate) -> bool {
+        let noise = 2.0 * (self.std_dev + previous.std_dev);
+
+        self.change_from(previous) > REGRESSION_THRESHOLD && self.median - previous.median > noise
+    }
+}
+
+/// Runs the benchmarks whose name contains the filter, comparing each against the saved
+/// baseline, or saving a new baseline. Returns false if any of them regressed.
+pub fn run_benchmarks(benchmarks: &[Benchmark], filter: Option<&str>, save_baseline: bool) -> bool {
+    let mut no_regressions = true;
+
+    for benchmark in benchmarks
+        .iter()
+        .filter(|benchmark| filter.is_none_or(|filter| benchmark.name.contains(filter)))
+    {
+        let baseline_path = baseline_path(benchmark.name);
+        let baseline = read_baseline(&baseline_path);
+        let mut measurements = Vec::new();
+
+        println!("{}", benchmark.name);
+        println!(
+            "  {:>10}  {:>10}  {:>22}  {:>12}  {:>11}",
+            "size", "median", "mean ± std dev", "per element", "vs baseline"
+        );
+
+        for &size in benchmark.sizes {
+            let mut bencher = Bencher::default();
+            (benchmark.run)(&mut bencher, size);
+
+            let estimate = Estimate::from_samples(&bencher.samples);
+            let previous = baseline
+                .iter()
+                .find(|(baseline_size, _)| *baseline_size == size)
+                .map(|(_, previous)| previous);
+
+            let change_text = match previous {
+                Some(previous) if estimate.regressed_from(previous) => {
+                    no_regressions = false;
+                    format!("{:+.1}% SLOWER", estimate.change_from(previous) * 100.0)
+                }
+                Some(previous) => format!("{:+.1}%", estimate.change_from(previous) * 100.0),
+                None => "-".to_string(),
+            };
+
+            println!(
+                "  {:>10}  {:>10}  {:>22}  {:>12}  {:>11}",
+                size,
+                format_nanos(estimate.median),
+                format!(
+                    "{} ± {}",
+                    format_nanos(estimate.mean),
+                    format_nanos(estimate.std_dev)
+                ),
+                format_nanos(estimate.median / size as f64),
+                change_text
+            );
+
+            measurements.push((size, estimate));
+        }
+
+        if let Some(exponent) = growth_exponent(&measurements) {
+            println!("  growth: ~n^{:.2}", exponent);
+        }
+
+        if save_baseline {
+            if let Err(error) = write_baseline(&baseline_path, &measurements) {
+                eprintln!(
+                    "Could not save baseline {}: {}",
+                    baseline_path.display(),
+                    error
+                );
+            }
+        }
+    }
+
+    no_regressions || save_baseline
+}
+
+/// Fits `time = c * size^k` to the measurements, returning `k`.
+fn growth_exponent(measurements: &[(usize, Estimate)]) -> Option<f64> {
+    if measurements.len() < 2 {
+        return None;
+    }
+
+    let points = measurements
+        .iter()
+        .map(|(size, estimate)| ((*size as f64).ln(), estimate.median.ln()))
+        .collect::<Vec<_>>();
+
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+
+    let covariance = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let variance = points
+        .iter()
+        .map(|(x, _)| (x - mean_x).powi(2))
+        .sum::<f64>();
+
+    Some(covariance / variance)
+}
+
+fn format_nanos(nanos: f64) -> String {
+    if nanos < 1e3 {
+        format!("{:.2} ns", nanos)
+    } else if nanos < 1e6 {
+        format!("{:.2} µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.2} ms", nanos / 1e6)
+    } else {
+        format!("{:.2} s", nanos / 1e9)
+    }
+}
+
+fn baseline_path(name: &str) -> PathBuf {
+    let file_name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+
+    Path::new(BASELINE_DIR).join(file_name + ".txt")
+}
+
+fn read_baseline(path: &Path) -> Vec<(usize, Estimate)> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    text.lines()
+        .filter_map(|line| {
+            let mut values = line.split(' ');
+            let size = values.next()?.parse().ok()?;
+            let mut estimate = values.map(|value| value.parse::<f64>());
+
+            Some((
+                size,
+                Estimate {
+                    median: estimate.next()?.ok()?,
+                    mean: estimate.next()?.ok()?,
+                    std_dev: estimate.next()?.ok()?,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn write_baseline(path: &Path, measurements: &[(usize, Estimate)]) -> std::io::Result<()> {
+    fs::create_dir_all(BASELINE_DIR)?;
+
+    let text = measurements
+        .iter()
+        .map(|(size, estimate)| {
+            format!(
+                "{} {} {} {}\n",
+                size, estimate.median, estimate.mean, estimate.std_dev
+            )
+        })
+        .collect::<String>();
+
+    fs::write(path, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate() {
+        let estimate = Estimate::from_samples(&[4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(estimate.median, 2.5);
+        assert_eq!(estimate.mean, 2.5);
+        assert!((estimate.std_dev - 1.25_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_regressed_from() {
+        let estimate = |median, std_dev| Estimate {
+            median,
+            mean: median,
+            std_dev,
+        };
+
+        assert!(estimate(150.0, 1.0).regressed_from(&estimate(100.0, 1.0)));
+        assert!(!estimate(105.0, 1.0).regressed_from(&estimate(100.0, 1.0)));
+        assert!(!estimate(150.0, 20.0).regressed_from(&estimate(100.0, 20.0)));
+        assert!(!estimate(50.0, 1.0).regressed_from(&estimate(100.0, 1.0)));
+    }
+
+    #[test]
+    fn test_growth_exponent() {
+        let measure = |points: &[(usize, f64)]| {
+            points
+                .iter()
+                .map(|(size, median)| {
+                    let estimate = Estimate {
+                        median: *median,
+                        mean: *median,
+                        std_dev: 0.0,
+                    };
+                    (*size, estimate)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let linear = measure(&[(10, 50.0), (100, 500.0), (1000, 5000.0)]);
+        assert!((growth_exponent(&linear).unwrap() - 1.0).abs() < 1e-9);
+
+        let quadratic = measure(&[(10, 1.0), (100, 100.0), (1000, 10000.0)]);
+        assert!((growth_exponent(&quadratic).unwrap() - 2.0).abs() < 1e-9);
+
+        assert_eq!(growth_exponent(&measure(&[(10, 1.0)])), None);
+    }
+
+    #[test]
+    fn test_format_nanos() {
+        assert_eq!(format_nanos(12.0), "12.00 ns");
+        assert_eq!(format_nanos(1_500.0), "1.50 µs");
+        assert_eq!(format_nanos(2_500_000.0), "2.50 ms");
+    }
+}
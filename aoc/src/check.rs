@@ -0,0 +1,220 @@
+use std::fmt::{self, Display};
+
+/// Checks the lines of an input, reporting every way they don't match what the day expects.
+pub type Grammar = fn(&[Line]) -> Vec<Violation>;
+
+/// A line of input, without its line ending.
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub line: usize,
+    pub column: usize,
+    pub problem: String,
+    pub fix: String,
+}
+
+impl Violation {
+    pub fn new(line: &Line, column: usize, problem: &str, fix: &str) -> Self {
+        Self {
+            line: line.number,
+            column,
+            problem: problem.to_string(),
+            fix: fix.to_string(),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {} ({})",
+            self.line, self.column, self.problem, self.fix
+        )
+    }
+}
+
+/// Splits the input into lines and checks it against the problems every day shares, then
+/// against the day's own grammar.
+pub fn check(input: &[u8], grammar: Option<Grammar>) -> Vec<Violation> {
+    let mut text = match std::str::from_utf8(input) {
+        Ok(text) => text,
+        Err(error) => {
+            let line = input[..error.valid_up_to()]
+                .iter()
+                .filter(|b| **b == b'\n')
+                .count();
+            return vec![Violation {
+                line: line + 1,
+                column: 1,
+                problem: "Input is not valid UTF-8".to_string(),
+                fix: "save the input as plain ASCII text".to_string(),
+            }];
+        }
+    };
+
+    if text.is_empty() {
+        return vec![Violation {
+            line: 1,
+            column: 1,
+            problem: "Input is empty".to_string(),
+            fix: "paste the puzzle input into the file".to_string(),
+        }];
+    }
+
+    let mut violations = Vec::new();
+    let mut lines = Vec::new();
+
+    if let Some(rest) = text.strip_prefix('\u{feff}') {
+        violations.push(Violation {
+            line: 1,
+            column: 1,
+            problem: "Input starts with a byte order mark".to_string(),
+            fix: "save the input without a BOM".to_string(),
+        });
+        text = rest;
+    }
+
+    for (index, raw) in text.split_terminator('\n').enumerate() {
+        let line = Line {
+            number: index + 1,
+            text: raw.strip_suffix('\r').unwrap_or(raw),
+        };
+
+        if line.text.len() < raw.len() {
+            violations.push(Violation::new(
+                &line,
+                raw.len(),
+                "Line ends with CRLF",
+                "convert line endings to LF",
+            ));
+        }
+
+        if let Some(column) = line.text.find(|c: char| !c.is_ascii()) {
+            violations.push(Violation::new(
+                &line,
+                column + 1,
+                "Line contains a non-ASCII character",
+                "remove the character or retype it as ASCII",
+            ));
+        }
+
+        lines.push(line);
+    }
+
+    // A last line without its line ending may have been cut off when copying the input
+    if let Some(last) = lines.last().filter(|_| !text.ends_with('\n')) {
+        violations.push(Violation::new(
+            last,
+            last.text.len() + 1,
+            "Input doesn't end with a line ending",
+            "add a newline after the last line",
+        ));
+    }
+
+    if let Some(grammar) = grammar {
+        violations.extend(grammar(&lines));
+    }
+
+    violations.sort_by_key(|violation| (violation.line, violation.column));
+
+    violations
+}
+
+/// Reports whitespace at the end of a line, which the line based parsers don't expect.
+pub fn check_trailing_whitespace(line: &Line) -> Option<Violation> {
+    let trimmed = line.text.trim_end();
+
+    if trimmed.len() < line.text.len() {
+        Some(Violation::new(
+            line,
+            trimmed.len() + 1,
+            "Line has trailing whitespace",
+            "remove the whitespace at the end of the line",
+        ))
+    } else {
+        None
+    }
+}
+
+/// Reports the first character of `text`, found at `column`, that isn't a digit.
+pub fn check_number(line: &Line, column: usize, text: &str) -> Option<Violation> {
+    if text.is_empty() {
+        return Some(Violation::new(
+            line,
+            column,
+            "Expected a number",
+            "add the missing number",
+        ));
+    }
+
+    text.find(|c: char| !c.is_ascii_digit()).map(|index| {
+        Violation::new(
+            line,
+            column + index,
+            "Expected a digit",
+            "remove the character that isn't part of the number",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits_only(lines: &[Line]) -> Vec<Violation> {
+        lines
+            .iter()
+            .filter_map(|line| check_number(line, 1, line.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_check_crlf_and_grammar() {
+        let violations = check(b"12\r\n3x\n", Some(digits_only));
+
+        assert_eq!(violations.len(), 2);
+        assert_eq!((violations[0].line, violations[0].column), (1, 3));
+        assert_eq!(violations[0].problem, "Line ends with CRLF");
+        assert_eq!((violations[1].line, violations[1].column), (2, 2));
+        assert_eq!(violations[1].problem, "Expected a digit");
+    }
+
+    #[test]
+    fn test_check_bom_and_empty() {
+        let violations = check("\u{feff}1\n".as_bytes(), Some(digits_only));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].problem, "Input starts with a byte order mark");
+
+        assert_eq!(check(b"", None)[0].problem, "Input is empty");
+    }
+
+    #[test]
+    fn test_check_final_newline() {
+        let violations = check(b"12\n34", Some(digits_only));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].line, violations[0].column), (2, 3));
+        assert_eq!(
+            violations[0].problem,
+            "Input doesn't end with a line ending"
+        );
+
+        assert!(check(b"12\n34\n", Some(digits_only)).is_empty());
+    }
+
+    #[test]
+    fn test_check_trailing_whitespace() {
+        let line = Line {
+            number: 4,
+            text: "1000 ",
+        };
+
+        let violation = check_trailing_whitespace(&line).unwrap();
+        assert_eq!((violation.line, violation.column), (4, 5));
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use crate::{isolate::Limits, Part};
+
+pub const USAGE: &str = "Usage: <day> [run|compare|check] [--input FILE] [--part 1|2]
+       <day> bench [FILTER] [--save-baseline]
+       <day> COMMAND [ARGS...] [--input FILE]";
+pub const RUNNER_USAGE: &str =
+    "Usage: aoc [run|check] [--day N] [--part 1|2] [--timeout SECONDS] [--memory MEGABYTES]";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Command {
+    Run,
+    Compare,
+    Check,
+    Bench,
+    /// A command registered by the day itself.
+    Custom(String),
+}
+
+#[derive(Debug)]
+pub struct Options {
+    pub command: Command,
+    pub input: String,
+    pub part: Option<Part>,
+    /// Only benchmarks with names containing this are run.
+    pub filter: Option<String>,
+    pub save_baseline: bool,
+    /// Arguments of a custom command.
+    pub args: Vec<String>,
+}
+
+impl Options {
+    /// Parses the arguments of a day, which registered the custom commands named.
+    pub fn parse(args: &[String], custom_commands: &[&str]) -> Result<Options, UsageError> {
+        let mut command = None;
+        let mut input = "input.txt".to_string();
+        let mut part = None;
+        let mut filter = None;
+        let mut save_baseline = false;
+        let mut custom_args = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => input = flag_value(arg, args.next())?.clone(),
+                _ if matches!(command, Some(Command::Custom(_))) => custom_args.push(arg.clone()),
+                "--part" => {
+                    let value = flag_value(arg, args.next())?;
+                    part = Some(Part::parse(value).ok_or_else(|| {
+                        UsageError(format!("Unknown part '{}', expected 1 or 2", value))
+                    })?);
+                }
+                "run" if command.is_none() => command = Some(Command::Run),
+                "compare" if command.is_none() => command = Some(Command::Compare),
+                "check" if command.is_none() => command = Some(Command::Check),
+                "bench" if command.is_none() => command = Some(Command::Bench),
+                "--save-baseline" => save_baseline = true,
+                _ if command == Some(Command::Bench)
+                    && filter.is_none()
+                    && !arg.starts_with("--") =>
+                {
+                    filter = Some(arg.clone())
+                }
+                // Unknown names are rejected here, before any input is read for them
+                _ if command.is_none() && custom_commands.contains(&arg.as_str()) => {
+                    command = Some(Command::Custom(arg.clone()))
+                }
+                _ if command.is_none() && !arg.starts_with("--") => {
+                    return Err(UsageError(format!("Unknown command '{}'", arg)))
+                }
+                _ => return Err(UsageError(format!("Unexpected argument '{}'", arg))),
+            }
+        }
+
+        Ok(Options {
+            command: command.unwrap_or(Command::Run),
+            input,
+            part,
+            filter,
+            save_baseline,
+            args: custom_args,
+        })
+    }
+}
+
+/// Options of the runner that runs every day, each in its own process.
+#[derive(Debug)]
+pub struct RunnerOptions {
+    pub command: Command,
+    pub day: Option<u32>,
+    pub part: Option<Part>,
+    pub limits: Limits,
+}
+
+impl RunnerOptions {
+    pub fn parse(args: &[String]) -> Result<RunnerOptions, UsageError> {
+        let mut options = RunnerOptions {
+            command: Command::Run,
+            day: None,
+            part: None,
+            limits: Limits {
+                timeout: Duration::from_secs(60),
+                memory_bytes: None,
+            },
+        };
+        let mut command_seen = false;
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--day" => options.day = Some(parse_number(arg, args.next())?),
+                "--part" => {
+                    let value = flag_value(arg, args.next())?;
+                    options.part = Some(Part::parse(value).ok_or_else(|| {
+                        UsageError(format!("Unknown part '{}', expected 1 or 2", value))
+                    })?);
+                }
+                "--timeout" => {
+                    options.limits.timeout = Duration::from_secs(parse_number(arg, args.next())?)
+                }
+                "--memory" => {
+                    let megabytes: u64 = parse_number(arg, args.next())?;
+                    let bytes = megabytes.checked_mul(1024 * 1024).ok_or_else(|| {
+                        UsageError(format!("{} of {} megabytes is too large", arg, megabytes))
+                    })?;
+                    options.limits.memory_bytes = Some(bytes);
+                }
+                "run" if !command_seen => command_seen = true,
+                "check" if !command_seen => {
+                    options.command = Command::Check;
+                    command_seen = true;
+                }
+                _ => return Err(UsageError(format!("Unexpected argument '{}'", arg))),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, UsageError> {
+    let value = flag_value(flag, value)?;
+    value
+        .parse()
+        .map_err(|_| UsageError(format!("{} expects a number, got '{}'", flag, value)))
+}
+
+pub fn flag_value<'a>(flag: &str, value: Option<&'a String>) -> Result<&'a String, UsageError> {
+    value.ok_or_else(|| UsageError(format!("{} requires a value", flag)))
+}
+
+#[derive(Debug)]
+pub struct UsageError(pub String);
+
+impl Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let options = Options::parse(&[], &[]).unwrap();
+        assert_eq!(options.command, Command::Run);
+        assert_eq!(options.input, "input.txt");
+    }
+
+    #[test]
+    fn test_parse_compare_with_input() {
+        let options = Options::parse(&args(&["compare", "--input", "example.txt"]), &[]).unwrap();
+        assert_eq!(options.command, Command::Compare);
+        assert_eq!(options.input, "example.txt");
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        let options = Options::parse(&args(&["bench", "view", "--save-baseline"]), &[]).unwrap();
+        assert_eq!(options.command, Command::Bench);
+        assert_eq!(options.filter.as_deref(), Some("view"));
+        assert!(options.save_baseline);
+
+        assert!(Options::parse(&args(&["run", "view"]), &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_custom() {
+        let options =
+            Options::parse(&args(&["--input", "x.txt", "top", "3", "--ties"]), &["top"]).unwrap();
+        assert_eq!(options.command, Command::Custom("top".to_string()));
+        assert_eq!(options.input, "x.txt");
+        assert_eq!(options.args, args(&["3", "--ties"]));
+    }
+
+    #[test]
+    fn test_parse_part() {
+        let options = Options::parse(&args(&["run", "--part", "2"]), &[]).unwrap();
+        assert_eq!(options.part, Some(Part::Two));
+    }
+
+    #[test]
+    fn test_parse_runner_limits() {
+        let options =
+            RunnerOptions::parse(&args(&["--day", "3", "--timeout", "5", "--memory", "64"]))
+                .unwrap();
+        assert_eq!(options.day, Some(3));
+        assert_eq!(options.limits.timeout, Duration::from_secs(5));
+        assert_eq!(options.limits.memory_bytes, Some(64 * 1024 * 1024));
+        assert!(RunnerOptions::parse(&args(&["--timeout", "soon"])).is_err());
+        assert!(RunnerOptions::parse(&args(&["--memory", &u64::MAX.to_string()])).is_err());
+
+        let options = RunnerOptions::parse(&args(&["check", "--day", "1"])).unwrap();
+        assert_eq!(options.command, Command::Check);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown() {
+        assert!(Options::parse(&args(&["frobnicate"]), &["top"]).is_err());
+        // A mistyped built in isn't taken for a custom command
+        assert!(Options::parse(&args(&["compre"]), &["top"]).is_err());
+        assert!(Options::parse(&args(&["--input"]), &[]).is_err());
+        assert!(Options::parse(&args(&["--part", "3"]), &[]).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display},
+};
+
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NonAsciiError {
+    pub line: usize,
+    pub column: usize,
+    pub byte: u8,
+}
+
+impl Display for NonAsciiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Input contains the non-ASCII byte 0x{:02x} at line {}, column {}. Puzzle inputs are plain ASCII, run the check command for details",
+            self.byte, self.line, self.column
+        )
+    }
+}
+
+/// Prepares raw input for the solvers, which index lines by byte and expect `\n` line endings:
+/// strips a byte order mark, turns CRLF line endings into LF and rejects anything that isn't
+/// ASCII. Input that needs no changes is passed through without copying.
+pub fn normalize(input: &[u8]) -> Result<Cow<'_, [u8]>, NonAsciiError> {
+    let input = input.strip_prefix(BOM).unwrap_or(input);
+
+    let mut line = 1;
+    let mut line_start = 0;
+
+    for (i, byte) in input.iter().enumerate() {
+        if !byte.is_ascii() {
+            return Err(NonAsciiError {
+                line,
+                column: i - line_start + 1,
+                byte: *byte,
+            });
+        }
+
+        if *byte == b'\n' {
+            line += 1;
+            line_start = i + 1;
+        }
+    }
+
+    if !input.windows(2).any(|pair| pair == b"\r\n") {
+        return Ok(Cow::Borrowed(input));
+    }
+
+    let mut normalized = Vec::with_capacity(input.len());
+
+    for (i, byte) in input.iter().enumerate() {
+        if *byte != b'\r' || input.get(i + 1) != Some(&b'\n') {
+            normalized.push(*byte);
+        }
+    }
+
+    Ok(Cow::Owned(normalized))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_unchanged() {
+        let normalized = normalize(b"1000\n\n2000\n").unwrap();
+        assert!(matches!(normalized, Cow::Borrowed(_)));
+        assert_eq!(&*normalized, b"1000\n\n2000\n");
+    }
+
+    #[test]
+    fn test_normalize_bom_and_crlf() {
+        let normalized = normalize(b"\xef\xbb\xbfA Y\r\nB X\r\nC Z").unwrap();
+        assert_eq!(&*normalized, b"A Y\nB X\nC Z");
+    }
+
+    #[test]
+    fn test_normalize_rejects_non_ascii() {
+        let error = normalize("vJrwpWtwJgWr\nhcsFMM\u{e9}fFFhFp\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            error,
+            NonAsciiError {
+                line: 2,
+                column: 7,
+                byte: 0xc3
+            }
+        );
+    }
+}
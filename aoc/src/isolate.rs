@@ -0,0 +1,229 @@
+use std::{
+    fmt::{self, Display},
+    io::{self, Read},
+    process::{Child, Command, ExitStatus, Stdio},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub timeout: Duration,
+    pub memory_bytes: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Finished(String),
+    Panic(String),
+    Timeout,
+    OutOfMemory,
+    Failed(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Finished(output) => write!(f, "{}", output.trim_end()),
+            Outcome::Panic(message) => write!(f, "PANIC: {}", message),
+            Outcome::Timeout => write!(f, "TIMEOUT"),
+            Outcome::OutOfMemory => write!(f, "OUT OF MEMORY"),
+            Outcome::Failed(message) => write!(f, "FAILED: {}", message),
+        }
+    }
+}
+
+pub struct Execution {
+    pub outcome: Outcome,
+    /// Everything the process wrote to standard output, whether it succeeded or not.
+    pub stdout: String,
+    pub elapsed: Duration,
+}
+
+/// Runs a command in its own process, killing it if it exceeds the time limit and capping the
+/// address space it may allocate.
+pub fn run(mut command: Command, limits: &Limits) -> io::Result<Execution> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    if let Some(memory_bytes) = limits.memory_bytes {
+        limit_memory(&mut command, memory_bytes);
+    }
+
+    let start = Instant::now();
+    let mut child = command.spawn()?;
+
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let status = wait_with_timeout(&mut child, limits.timeout)?;
+    let elapsed = start.elapsed();
+
+    let Some(status) = status else {
+        // The pipes may still be held open by grandchildren, so don't wait for them to close
+        return Ok(Execution {
+            outcome: Outcome::Timeout,
+            stdout: String::new(),
+            elapsed,
+        });
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    let outcome = if status.success() {
+        Outcome::Finished(stdout.clone())
+    } else if let Some(message) = find_panic_message(&stderr) {
+        Outcome::Panic(message)
+    } else if stderr.contains("memory allocation of") {
+        Outcome::OutOfMemory
+    } else {
+        Outcome::Failed(format!("{}: {}", status, stderr.trim()))
+    };
+
+    Ok(Execution {
+        outcome,
+        stdout,
+        elapsed,
+    })
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut output);
+        }
+
+        output
+    })
+}
+
+/// Finds the message of a Rust panic in the standard error output of a process, followed by
+/// the location it panicked at.
+fn find_panic_message(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines();
+
+    while let Some(line) = lines.next() {
+        if let Some(index) = line.find("panicked at ") {
+            let location = line[index + "panicked at ".len()..].trim_end_matches(':');
+            let message = lines.next().unwrap_or_default();
+
+            return Some(format!("{} at {}", message, location));
+        }
+    }
+
+    None
+}
+
+#[cfg(unix)]
+fn limit_memory(command: &mut Command, memory_bytes: u64) {
+    use std::os::unix::process::CommandExt;
+
+    let limit = libc::rlimit {
+        rlim_cur: memory_bytes as libc::rlim_t,
+        rlim_max: memory_bytes as libc::rlim_t,
+    };
+
+    // Safety: setrlimit is async-signal-safe and only touches the forked child.
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setrlimit(libc::RLIMIT_AS, &limit) == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_memory(_command: &mut Command, _memory_bytes: u64) {
+    eprintln!("Memory limits are only supported on unix, running without one");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> Limits {
+        Limits {
+            timeout: Duration::from_secs(5),
+            memory_bytes: None,
+        }
+    }
+
+    fn shell(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        command
+    }
+
+    #[test]
+    fn test_find_panic_message() {
+        let stderr = "\nthread 'main' panicked at src/main.rs:21:57:\ncalled `Option::unwrap()` on a `None` value\nnote: run with `RUST_BACKTRACE=1`\n";
+
+        assert_eq!(
+            find_panic_message(stderr),
+            Some("called `Option::unwrap()` on a `None` value at src/main.rs:21:57".to_string())
+        );
+        assert_eq!(find_panic_message("error: no such file"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_finished() {
+        let execution = run(shell("echo 'Answer 1: 42'"), &limits()).unwrap();
+        assert_eq!(
+            execution.outcome,
+            Outcome::Finished("Answer 1: 42\n".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_panic() {
+        let script = "echo \"thread 'main' panicked at src/main.rs:1:1:\nboom\" >&2; exit 101";
+        let execution = run(shell(script), &limits()).unwrap();
+        assert_eq!(
+            execution.outcome,
+            Outcome::Panic("boom at src/main.rs:1:1".to_string())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_timeout() {
+        let limits = Limits {
+            timeout: Duration::from_millis(50),
+            memory_bytes: None,
+        };
+
+        let execution = run(shell("sleep 10"), &limits).unwrap();
+        assert_eq!(execution.outcome, Outcome::Timeout);
+        assert!(execution.elapsed < Duration::from_secs(5));
+    }
+}
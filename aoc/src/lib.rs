@@ -0,0 +1,303 @@
+use std::{
+    borrow::Cow,
+    env,
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    process,
+};
+
+pub mod bench;
+pub mod check;
+mod cli;
+pub mod input;
+pub mod isolate;
+pub mod rng;
+pub mod runner;
+mod solution;
+
+pub use bench::{Bencher, Benchmark};
+pub use check::{Grammar, Line, Violation};
+pub use solution::{Comparison, FallibleSolver, Part, Run, Solution, Solve, Solver};
+
+/// Runs a day specific command on the normalized input with the arguments given after its name.
+pub type CommandHandler = fn(&[u8], &[String]) -> Result<(), String>;
+
+/// Runs a day specific command that reads the input as it goes, for inputs too large to load.
+/// The input is not normalized.
+pub type StreamingCommandHandler = fn(&mut dyn BufRead, &[String]) -> Result<(), String>;
+
+/// Runs a day specific command that doesn't read the input, such as one generating inputs.
+pub type NoInputCommandHandler = fn(&[String]) -> Result<(), String>;
+
+enum Handler {
+    Buffered(CommandHandler),
+    Streaming(StreamingCommandHandler),
+    NoInput(NoInputCommandHandler),
+}
+
+struct CustomCommand {
+    name: &'static str,
+    usage: &'static str,
+    handler: Handler,
+}
+
+use cli::{Command, Options};
+
+/// The registered solutions of a day, and the command line entry point that runs them.
+#[derive(Default)]
+pub struct Day {
+    solutions: Vec<Solution>,
+    grammar: Option<Grammar>,
+    benchmarks: Vec<Benchmark>,
+    commands: Vec<CustomCommand>,
+}
+
+impl Day {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an implementation of a part. The first one registered for a part is the one
+    /// used when just asking for the answers.
+    pub fn solution(mut self, part: Part, name: &'static str, solve: Solver) -> Self {
+        self.solutions.push(Solution {
+            part,
+            name,
+            solve: Solve::Infallible(solve),
+        });
+        self
+    }
+
+    /// Registers an implementation of a part that reports when the input has no answer, instead
+    /// of panicking.
+    pub fn fallible_solution(
+        mut self,
+        part: Part,
+        name: &'static str,
+        solve: FallibleSolver,
+    ) -> Self {
+        self.solutions.push(Solution {
+            part,
+            name,
+            solve: Solve::Fallible(solve),
+        });
+        self
+    }
+
+    /// Sets the grammar the `check` command validates the input against.
+    pub fn grammar(mut self, grammar: Grammar) -> Self {
+        self.grammar = Some(grammar);
+        self
+    }
+
+    /// Registers a benchmark of one of the day's hot functions, run on generated inputs of each
+    /// of the sizes.
+    pub fn benchmark(
+        mut self,
+        name: &'static str,
+        sizes: &'static [usize],
+        run: fn(&mut Bencher, usize),
+    ) -> Self {
+        self.benchmarks.push(Benchmark { name, sizes, run });
+        self
+    }
+
+    /// Registers a command of the day's own, e.g. `day1 stats`. The usage describes its
+    /// arguments, and is shown with the built in commands.
+    pub fn command(mut self, name: &'static str, usage: &'static str, run: CommandHandler) -> Self {
+        self.commands.push(CustomCommand {
+            name,
+            usage,
+            handler: Handler::Buffered(run),
+        });
+        self
+    }
+
+    /// Registers a command of the day's own that streams the input instead of loading it.
+    /// `--input -` streams standard input.
+    pub fn streaming_command(
+        mut self,
+        name: &'static str,
+        usage: &'static str,
+        run: StreamingCommandHandler,
+    ) -> Self {
+        self.commands.push(CustomCommand {
+            name,
+            usage,
+            handler: Handler::Streaming(run),
+        });
+        self
+    }
+
+    /// Registers a command of the day's own that doesn't read the input, so it runs without
+    /// one.
+    pub fn no_input_command(
+        mut self,
+        name: &'static str,
+        usage: &'static str,
+        run: NoInputCommandHandler,
+    ) -> Self {
+        self.commands.push(CustomCommand {
+            name,
+            usage,
+            handler: Handler::NoInput(run),
+        });
+        self
+    }
+
+    pub fn run(self) {
+        let args = env::args().skip(1).collect::<Vec<_>>();
+
+        let command_names = self
+            .commands
+            .iter()
+            .map(|command| command.name)
+            .collect::<Vec<_>>();
+        let options = Options::parse(&args, &command_names)
+            .unwrap_or_else(|error| self.exit_with_usage(error));
+
+        let succeeded = match options.command {
+            Command::Bench => bench::run_benchmarks(
+                &self.benchmarks,
+                options.filter.as_deref(),
+                options.save_baseline,
+            ),
+            // Checking reports the problems normalizing would hide or reject, so it sees the raw
+            // input
+            Command::Check => self.print_violations(&read_input(&options.input), &options.input),
+            Command::Custom(ref name) => self.run_command(name, &options),
+            Command::Run => {
+                self.print_answers(&read_normalized_input(&options.input), options.part)
+            }
+            Command::Compare => {
+                self.print_comparisons(&read_normalized_input(&options.input), options.part)
+            }
+        };
+
+        if !succeeded {
+            process::exit(1);
+        }
+    }
+
+    fn run_command(&self, name: &str, options: &Options) -> bool {
+        // Parsing only accepts the names of registered commands
+        let command = self
+            .commands
+            .iter()
+            .find(|command| command.name == name)
+            .unwrap();
+
+        let result = match command.handler {
+            Handler::Buffered(run) => run(&read_normalized_input(&options.input), &options.args),
+            Handler::Streaming(run) => run(&mut open_input(&options.input), &options.args),
+            Handler::NoInput(run) => run(&options.args),
+        };
+
+        match result {
+            Ok(()) => true,
+            Err(error) => {
+                eprintln!("{}\nUsage: <day> {} {}", error, command.name, command.usage);
+                false
+            }
+        }
+    }
+
+    fn exit_with_usage<E: std::fmt::Display>(&self, error: E) -> ! {
+        eprintln!("{}\n{}", error, cli::USAGE);
+
+        for command in &self.commands {
+            eprintln!("       <day> {} {}", command.name, command.usage);
+        }
+
+        process::exit(2);
+    }
+
+    fn print_answers(&self, input: &[u8], part: Option<Part>) -> bool {
+        let mut all_answered = true;
+
+        for part in selected_parts(part) {
+            if let Some(solution) = self.solutions.iter().find(|s| s.part == part) {
+                match solution.run(input).answer {
+                    Ok(answer) => println!("Answer {}: {}", part, answer),
+                    Err(error) => {
+                        eprintln!("Part {}: {}", part, error);
+                        all_answered = false;
+                    }
+                }
+            }
+        }
+
+        all_answered
+    }
+
+    fn print_comparisons(&self, input: &[u8], part: Option<Part>) -> bool {
+        let mut all_agree = true;
+
+        for part in selected_parts(part) {
+            let comparison = Comparison::new(&self.solutions, part, input);
+
+            if !comparison.runs.is_empty() {
+                print!("{}", comparison);
+                all_agree &= comparison.agrees();
+            }
+        }
+
+        all_agree
+    }
+
+    fn print_violations(&self, input: &[u8], path: &str) -> bool {
+        let violations = check::check(input, self.grammar);
+
+        for violation in &violations {
+            println!("{}:{}", path, violation);
+        }
+
+        if violations.is_empty() {
+            println!("{}: OK", path);
+        }
+
+        violations.is_empty()
+    }
+}
+
+fn read_input(path: &str) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|error| {
+        eprintln!("Could not read {}: {}", path, error);
+        process::exit(1);
+    })
+}
+
+fn read_normalized_input(path: &str) -> Vec<u8> {
+    let input = read_input(path);
+
+    match input::normalize(&input) {
+        // Avoid copying input that needed no changes
+        Ok(Cow::Borrowed(normalized)) if normalized.len() == input.len() => input,
+        Ok(normalized) => normalized.into_owned(),
+        Err(error) => {
+            eprintln!("{}: {}", path, error);
+            process::exit(1);
+        }
+    }
+}
+
+fn open_input(path: &str) -> Box<dyn BufRead> {
+    if path == "-" {
+        return Box::new(io::stdin().lock());
+    }
+
+    match File::open(path) {
+        Ok(file) => Box::new(BufReader::new(file)),
+        Err(error) => {
+            eprintln!("Could not read {}: {}", path, error);
+            process::exit(1);
+        }
+    }
+}
+
+fn selected_parts(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+};
+
+use crate::{
+    cli::{Command as CliCommand, RunnerOptions, RUNNER_USAGE},
+    isolate::{self, Outcome},
+    Part,
+};
+
+/// A day crate next to the runner, e.g. `day7/`.
+pub struct DayCrate {
+    pub number: u32,
+    pub dir: PathBuf,
+}
+
+impl DayCrate {
+    /// Builds the day in release mode, returning the path of its binary.
+    fn build(&self) -> Result<PathBuf, String> {
+        let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+        let output = Command::new(cargo)
+            .args(["build", "--release", "--quiet"])
+            .current_dir(&self.dir)
+            .output()
+            .map_err(|error| error.to_string())?;
+
+        if output.status.success() {
+            Ok(self
+                .target_dir()
+                .join("release")
+                .join(format!("day{}", self.number)))
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+
+    /// Where cargo puts the day's build, `CARGO_TARGET_DIR` if set, relative to the day.
+    fn target_dir(&self) -> PathBuf {
+        match env::var_os("CARGO_TARGET_DIR") {
+            Some(dir) => self.dir.join(dir),
+            None => self.dir.join("target"),
+        }
+    }
+}
+
+pub fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let options = RunnerOptions::parse(&args).unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, RUNNER_USAGE);
+        process::exit(2);
+    });
+
+    let days = find_days(&repository_root()).unwrap_or_else(|error| {
+        eprintln!("Could not list days: {}", error);
+        process::exit(1);
+    });
+
+    if !run_days(&days, &options) {
+        process::exit(1);
+    }
+}
+
+fn repository_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+pub fn find_days(root: &Path) -> std::io::Result<Vec<DayCrate>> {
+    let mut days = Vec::new();
+
+    for entry in fs::read_dir(root)? {
+        let dir = entry?.path();
+        let number = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("day"))
+            .and_then(|number| number.parse::<u32>().ok());
+
+        if let Some(number) = number {
+            if dir.join("Cargo.toml").is_file() {
+                days.push(DayCrate { number, dir });
+            }
+        }
+    }
+
+    days.sort_by_key(|day| day.number);
+
+    Ok(days)
+}
+
+/// Runs every selected day and part in its own process, so that one of them panicking, looping
+/// forever or running out of memory doesn't stop the others. Returns whether all of them
+/// produced an answer, or when checking, whether all inputs are valid.
+fn run_days(days: &[DayCrate], options: &RunnerOptions) -> bool {
+    let parts = match options.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+    let mut all_answered = true;
+
+    for day in days
+        .iter()
+        .filter(|day| options.day.is_none_or(|number| number == day.number))
+    {
+        let binary = match day.build() {
+            Ok(binary) => binary,
+            Err(error) => {
+                println!("Day {}: BUILD FAILED: {}", day.number, error);
+                all_answered = false;
+                continue;
+            }
+        };
+
+        if options.command == CliCommand::Check {
+            all_answered &= check_day(day, &binary, options);
+            continue;
+        }
+
+        for part in &parts {
+            let mut command = Command::new(&binary);
+            command
+                .args(["run", "--part", &part.to_string()])
+                .current_dir(&day.dir);
+
+            let report = match isolate::run(command, &options.limits) {
+                Ok(execution) => {
+                    let outcome = match execution.outcome {
+                        Outcome::Finished(output) => Outcome::Finished(find_answer(&output)),
+                        outcome => {
+                            all_answered = false;
+                            outcome
+                        }
+                    };
+                    format!("{} ({:?})", outcome, execution.elapsed)
+                }
+                Err(error) => {
+                    all_answered = false;
+                    format!("FAILED TO START: {}", error)
+                }
+            };
+
+            println!("Day {} part {}: {}", day.number, part, report);
+        }
+    }
+
+    all_answered
+}
+
+/// Checks the input of a day, printing every violation its grammar finds.
+fn check_day(day: &DayCrate, binary: &Path, options: &RunnerOptions) -> bool {
+    let mut command = Command::new(binary);
+    command.arg("check").current_dir(&day.dir);
+
+    match isolate::run(command, &options.limits) {
+        Ok(execution) => {
+            let passed = matches!(execution.outcome, Outcome::Finished(_));
+
+            println!(
+                "Day {}: {}",
+                day.number,
+                if passed { "OK" } else { "INVALID" }
+            );
+
+            for line in execution.stdout.lines() {
+                println!("  {}", line);
+            }
+
+            if !passed && execution.stdout.is_empty() {
+                println!("  {}", execution.outcome);
+            }
+
+            passed
+        }
+        Err(error) => {
+            println!("Day {}: FAILED TO START: {}", day.number, error);
+            false
+        }
+    }
+}
+
+fn find_answer(output: &str) -> String {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Answer ")?.split_once(": "))
+        .map(|(_, answer)| answer.to_string())
+        .unwrap_or_else(|| output.trim().to_string())
+}
@@ -0,0 +1,211 @@
+use std::{
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+pub type Solver = fn(&[u8]) -> String;
+
+/// Solves a part whose input can be valid yet have no answer, explaining why.
+pub type FallibleSolver = fn(&[u8]) -> Result<String, String>;
+
+pub enum Solve {
+    Infallible(Solver),
+    Fallible(FallibleSolver),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn parse(text: &str) -> Option<Part> {
+        match text {
+            "1" => Some(Part::One),
+            "2" => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A named implementation of one part of a day.
+pub struct Solution {
+    pub part: Part,
+    pub name: &'static str,
+    pub solve: Solve,
+}
+
+impl Solution {
+    pub fn run(&self, input: &[u8]) -> Run {
+        let start = Instant::now();
+        let answer = match self.solve {
+            Solve::Infallible(solve) => Ok(solve(input)),
+            Solve::Fallible(solve) => solve(input),
+        };
+
+        Run {
+            answer,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+pub struct Run {
+    /// The answer, or why there is none.
+    pub answer: Result<String, String>,
+    pub elapsed: Duration,
+}
+
+/// Every registered implementation of a part, run on the same input.
+pub struct Comparison<'a> {
+    pub part: Part,
+    pub runs: Vec<(&'a Solution, Run)>,
+}
+
+impl<'a> Comparison<'a> {
+    pub fn new(solutions: &'a [Solution], part: Part, input: &[u8]) -> Self {
+        let runs = solutions
+            .iter()
+            .filter(|solution| solution.part == part)
+            .map(|solution| (solution, solution.run(input)))
+            .collect();
+
+        Self { part, runs }
+    }
+
+    pub fn agrees(&self) -> bool {
+        self.runs
+            .windows(2)
+            .all(|pair| pair[0].1.answer == pair[1].1.answer)
+    }
+
+    fn fastest(&self) -> Option<Duration> {
+        self.runs.iter().map(|(_, run)| run.elapsed).min()
+    }
+}
+
+impl Display for Comparison<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fastest = self.fastest().unwrap_or_default();
+        let name_width = self
+            .runs
+            .iter()
+            .map(|(solution, _)| solution.name.len())
+            .max()
+            .unwrap_or(0);
+
+        writeln!(f, "Part {}:", self.part)?;
+
+        for (solution, run) in &self.runs {
+            let relative = run.elapsed.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON);
+
+            let answer = match &run.answer {
+                Ok(answer) => answer.clone(),
+                Err(error) => format!("ERROR: {}", error),
+            };
+
+            writeln!(
+                f,
+                "  {:<name_width$}  {:>12?}  {:>7.2}x  {}",
+                solution.name, run.elapsed, relative, answer
+            )?;
+        }
+
+        if !self.agrees() {
+            writeln!(f, "  MISMATCH: implementations disagree")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_bytes(input: &[u8]) -> String {
+        input.len().to_string()
+    }
+
+    fn count_lines(input: &[u8]) -> String {
+        input.split(|b| *b == b'\n').count().to_string()
+    }
+
+    #[test]
+    fn test_comparison_agrees() {
+        let solutions = [
+            Solution {
+                part: Part::One,
+                name: "len",
+                solve: Solve::Infallible(count_bytes),
+            },
+            Solution {
+                part: Part::One,
+                name: "also len",
+                solve: Solve::Infallible(count_bytes),
+            },
+            Solution {
+                part: Part::Two,
+                name: "lines",
+                solve: Solve::Infallible(count_lines),
+            },
+        ];
+
+        let comparison = Comparison::new(&solutions, Part::One, b"abc");
+        assert_eq!(comparison.runs.len(), 2);
+        assert!(comparison.agrees());
+    }
+
+    #[test]
+    fn test_fallible_solution() {
+        let solutions = [
+            Solution {
+                part: Part::One,
+                name: "len",
+                solve: Solve::Infallible(count_bytes),
+            },
+            Solution {
+                part: Part::One,
+                name: "no answer",
+                solve: Solve::Fallible(|_| Err("Nothing to count".to_string())),
+            },
+        ];
+
+        let comparison = Comparison::new(&solutions, Part::One, b"abc");
+        assert_eq!(comparison.runs[0].1.answer, Ok("3".to_string()));
+        assert!(!comparison.agrees());
+        assert!(comparison.to_string().contains("ERROR: Nothing to count"));
+    }
+
+    #[test]
+    fn test_comparison_disagrees() {
+        let solutions = [
+            Solution {
+                part: Part::One,
+                name: "len",
+                solve: Solve::Infallible(count_bytes),
+            },
+            Solution {
+                part: Part::One,
+                name: "lines",
+                solve: Solve::Infallible(count_lines),
+            },
+        ];
+
+        let comparison = Comparison::new(&solutions, Part::One, b"a\nb");
+        assert!(!comparison.agrees());
+        assert!(comparison.to_string().contains("MISMATCH"));
+    }
+}
@@ -0,0 +1,348 @@
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fmt::{self, Display},
+    io::BufRead,
+};
+
+/// Calories of a single item.
+pub type Calories = u64;
+/// Calories of several items added up. Huge generated inventories overflow a `u64`, but a `u128`
+/// can't overflow until there are more than 2^64 items of `Calories::MAX`.
+pub type Total = u128;
+
+/// An elf and the calories of each item it carries, in the order they were listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    /// Position of the elf in the input, counting from 0.
+    pub index: usize,
+    /// The id the input gives the elf, for formats that name them.
+    pub id: Option<String>,
+    pub items: Vec<Calories>,
+}
+
+impl Elf {
+    pub fn total(&self) -> Total {
+        self.items.iter().map(|item| Total::from(*item)).sum()
+    }
+}
+
+impl Display for Elf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.id {
+            Some(id) => write!(f, "Elf {}", id)?,
+            None => write!(f, "Elf {}", self.index + 1)?,
+        }
+
+        write!(
+            f,
+            ": {} calories in {} items",
+            self.total(),
+            self.items.len()
+        )
+    }
+}
+
+/// Groups the items into elves, a blank line starting the next elf. A blank line ending the input
+/// starts none, so it doesn't add an elf carrying nothing.
+pub fn parse_elves<R: BufRead>(input: R) -> Vec<Elf> {
+    let first_elf = Elf {
+        index: 0,
+        id: None,
+        items: Vec::new(),
+    };
+
+    let mut elves = input.lines().fold(vec![first_elf], |mut elves, line| {
+        let line = line.unwrap();
+
+        if line.is_empty() {
+            elves.push(Elf {
+                index: elves.len(),
+                id: None,
+                items: Vec::new(),
+            });
+        } else {
+            let last_elf = elves.last_mut().unwrap();
+            last_elf.items.push(line.parse::<Calories>().unwrap());
+        }
+
+        elves
+    });
+
+    if elves.len() > 1 && elves.last().is_some_and(|elf| elf.items.is_empty()) {
+        elves.pop();
+    }
+
+    elves
+}
+
+/// The elves carrying the most calories.
+#[derive(Debug)]
+pub struct Ranking<'a> {
+    /// The top elves, most calories first. Elves carrying the same are in input order.
+    pub top: Vec<&'a Elf>,
+    /// Elves that didn't make it into the top, but carry as much as the last elf that did.
+    pub tied: Vec<&'a Elf>,
+}
+
+impl Ranking<'_> {
+    pub fn total(&self) -> Total {
+        self.top.iter().map(|elf| elf.total()).sum()
+    }
+}
+
+pub fn top_k(elves: &[Elf], k: usize) -> Ranking<'_> {
+    let mut ranked = elves.iter().collect::<Vec<_>>();
+    ranked.sort_by_cached_key(|elf| Reverse(elf.total()));
+
+    let split = k.min(ranked.len());
+    let rest = ranked.split_off(split);
+
+    let tied = match ranked.last() {
+        Some(last) => rest
+            .into_iter()
+            .take_while(|elf| elf.total() == last.total())
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ranking { top: ranked, tied }
+}
+
+/// The total of an elf, without the items that made it up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfTotal {
+    pub index: usize,
+    pub total: Total,
+}
+
+impl From<&Elf> for ElfTotal {
+    fn from(elf: &Elf) -> Self {
+        Self {
+            index: elf.index,
+            total: elf.total(),
+        }
+    }
+}
+
+/// Finds the top elves while reading, so memory use doesn't grow with the number of elves. Gives
+/// the same elves in the same order as `top_k`, but can't know which elves are tied with the last
+/// one.
+pub fn top_k_streaming<R: BufRead>(mut input: R, k: usize) -> Result<Vec<ElfTotal>, String> {
+    let mut line = String::new();
+    let mut line_number = 0;
+    let mut index = 0;
+    let mut total = 0;
+    let mut items = 0;
+    let mut done = false;
+    let mut error = None;
+
+    let totals = std::iter::from_fn(|| {
+        while !done {
+            line.clear();
+            line_number += 1;
+
+            match input.read_line(&mut line) {
+                Ok(0) => {
+                    done = true;
+                    // As in `parse_elves`, a blank line ending the input starts no elf
+                    return (index == 0 || items > 0).then_some(ElfTotal { index, total });
+                }
+                Ok(_) => {}
+                Err(read_error) => {
+                    error = Some(format!("Line {}: {}", line_number, read_error));
+                    return None;
+                }
+            }
+
+            // Streamed input isn't normalized, so it may still have a BOM and CRLF line endings
+            let mut calories = line.trim_end_matches(['\n', '\r']);
+
+            if line_number == 1 {
+                calories = calories.trim_start_matches('\u{feff}');
+            }
+
+            if calories.is_empty() {
+                let elf = ElfTotal { index, total };
+                index += 1;
+                total = 0;
+                items = 0;
+                return Some(elf);
+            }
+
+            match calories.parse::<Calories>() {
+                Ok(calories) => {
+                    total += Total::from(calories);
+                    items += 1;
+                }
+                Err(_) => {
+                    error = Some(format!(
+                        "Line {}: Expected a number of calories, got '{}'",
+                        line_number, calories
+                    ));
+                    return None;
+                }
+            }
+        }
+
+        None
+    });
+
+    let top = top_k_totals(totals, k);
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(top),
+    }
+}
+
+/// Keeps only the `k` best totals seen so far in a min-heap.
+pub fn top_k_totals<I: IntoIterator<Item = ElfTotal>>(totals: I, k: usize) -> Vec<ElfTotal> {
+    // Ordered so the heap's minimum is the elf to drop first: the lowest total, and of equal
+    // totals the one furthest into the input. It isn't preallocated, as k may be far more than
+    // the number of elves
+    let mut heap = BinaryHeap::<Reverse<(Total, Reverse<usize>)>>::new();
+
+    for ElfTotal { index, total } in totals {
+        heap.push(Reverse((total, Reverse(index))));
+
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((total, Reverse(index)))| ElfTotal { index, total })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elves(totals: &[Calories]) -> Vec<Elf> {
+        totals
+            .iter()
+            .enumerate()
+            .map(|(index, total)| Elf {
+                index,
+                id: None,
+                items: vec![*total],
+            })
+            .collect()
+    }
+
+    fn indices(elves: &[&Elf]) -> Vec<usize> {
+        elves.iter().map(|elf| elf.index).collect()
+    }
+
+    #[test]
+    fn test_parse_elves() {
+        let elves = parse_elves("1\n2\n\n\n3\n".as_bytes());
+
+        assert_eq!(elves.len(), 3);
+        assert_eq!(elves[0].items, vec![1, 2]);
+        assert_eq!(elves[1].items, Vec::<Calories>::new());
+        assert_eq!(elves[2].index, 2);
+    }
+
+    #[test]
+    fn test_trailing_blank_line() {
+        let elves = parse_elves("1000\n\n2000\n\n".as_bytes());
+
+        assert_eq!(elves.len(), 2);
+        assert_eq!(elves[1].items, vec![2000]);
+        assert_eq!(parse_elves("".as_bytes()).len(), 1);
+        assert_eq!(
+            top_k_streaming("1000\n\n2000\n\n".as_bytes(), 3),
+            Ok(vec![
+                ElfTotal {
+                    index: 1,
+                    total: 2000
+                },
+                ElfTotal {
+                    index: 0,
+                    total: 1000
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_top_k_ties() {
+        let elves = elves(&[5, 9, 7, 9, 7, 1]);
+
+        let ranking = top_k(&elves, 3);
+        assert_eq!(indices(&ranking.top), vec![1, 3, 2]);
+        assert_eq!(indices(&ranking.tied), vec![4]);
+        assert_eq!(ranking.total(), 25);
+
+        let ranking = top_k(&elves, 1);
+        assert_eq!(indices(&ranking.top), vec![1]);
+        assert_eq!(indices(&ranking.tied), vec![3]);
+    }
+
+    #[test]
+    fn test_top_k_streaming_matches_top_k() {
+        let input = "5\n\n4\n5\n\n7\n\n9\n\n3\n6\n\n1\n";
+        let elves = parse_elves(input.as_bytes());
+
+        for k in 0..8 {
+            let expected = top_k(&elves, k)
+                .top
+                .into_iter()
+                .map(ElfTotal::from)
+                .collect::<Vec<_>>();
+
+            assert_eq!(top_k_streaming(input.as_bytes(), k), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_totals_beyond_u64() {
+        let max = Calories::MAX;
+        let input = format!("{}\n{}\n\n1\n", max, max);
+        let expected = Total::from(max) * 2;
+
+        let elves = parse_elves(input.as_bytes());
+        assert_eq!(elves[0].total(), expected);
+        assert_eq!(top_k(&elves, 2).total(), expected + 1);
+
+        let top = top_k_streaming(input.as_bytes(), 1);
+        assert_eq!(
+            top,
+            Ok(vec![ElfTotal {
+                index: 0,
+                total: expected
+            }])
+        );
+    }
+
+    #[test]
+    fn test_top_k_streaming_unnormalized() {
+        let top = top_k_streaming("\u{feff}1\r\n2\r\n\r\n4\r\n".as_bytes(), 1);
+        assert_eq!(top, Ok(vec![ElfTotal { index: 1, total: 4 }]));
+
+        assert_eq!(
+            top_k_streaming("1\n\n2x\n".as_bytes(), 1),
+            Err("Line 3: Expected a number of calories, got '2x'".to_string())
+        );
+        assert!(top_k_streaming(&b"1\n\xff\n"[..], 1)
+            .unwrap_err()
+            .starts_with("Line 2: "));
+    }
+
+    #[test]
+    fn test_top_k_more_than_elves() {
+        let elves = elves(&[5, 9]);
+
+        let ranking = top_k(&elves, 5);
+        assert_eq!(indices(&ranking.top), vec![1, 0]);
+        assert!(ranking.tied.is_empty());
+        assert!(top_k(&elves, 0).top.is_empty());
+
+        let totals = elves.iter().map(ElfTotal::from);
+        assert_eq!(top_k_totals(totals, usize::MAX).len(), 2);
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use crate::elves::{parse_elves, Calories, Elf};
+
+/// Reads inventories written in one format into elves, so every query works on any of them.
+pub struct Decoder {
+    pub name: &'static str,
+    /// Whether the input looks like it's written in this format.
+    pub detect: fn(&str) -> bool,
+    pub decode: fn(&str) -> Result<Vec<Elf>, DecodeError>,
+}
+
+/// The puzzle's own format: the calories of each item on a line, a blank line between elves.
+pub const GROUPS: Decoder = Decoder {
+    name: "groups",
+    detect: |_| true,
+    decode: decode_groups,
+};
+
+/// An `elf_id,calories` row per item, optionally under a header naming those columns. Elves are
+/// numbered in the order their ids first appear, and keep their ids.
+pub const CSV: Decoder = Decoder {
+    name: "CSV",
+    detect: |text| {
+        text.lines()
+            .find(|line| !line.is_empty())
+            .is_some_and(|line| line.contains(','))
+    },
+    decode: decode_csv,
+};
+
+/// An array holding an array of item calories for each elf, e.g. `[[1000, 2000], [3000]]`.
+pub const JSON: Decoder = Decoder {
+    name: "JSON",
+    detect: |text| text.trim_start().starts_with('['),
+    decode: decode_json,
+};
+
+/// Tried in order, so the formats that are easy to recognize come before the one that isn't.
+pub const DECODERS: &[Decoder] = &[JSON, CSV, GROUPS];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl DecodeError {
+    fn new(line: usize, column: usize, message: &str) -> Self {
+        Self {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+pub fn detect(text: &str) -> &'static Decoder {
+    DECODERS
+        .iter()
+        .find(|decoder| (decoder.detect)(text))
+        .unwrap_or(&GROUPS)
+}
+
+/// Decodes the elves with the decoder for the format the input looks like it's written in.
+pub fn decode_elves(input: &[u8]) -> Result<Vec<Elf>, DecodeError> {
+    let text = std::str::from_utf8(input)
+        .map_err(|_| DecodeError::new(1, 1, "Input is not valid UTF-8"))?;
+
+    (detect(text).decode)(text)
+}
+
+fn decode_groups(text: &str) -> Result<Vec<Elf>, DecodeError> {
+    for (i, line) in text.lines().enumerate() {
+        if !line.is_empty() && line.parse::<Calories>().is_err() {
+            return Err(DecodeError::new(i + 1, 1, "Expected a number of calories"));
+        }
+    }
+
+    Ok(parse_elves(text.as_bytes()))
+}
+
+fn decode_csv(text: &str) -> Result<Vec<Elf>, DecodeError> {
+    let mut positions = HashMap::new();
+    let mut elves = Vec::<Elf>::new();
+
+    for (i, line) in text.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some((id, calories)) = line.split_once(',') else {
+            return Err(DecodeError::new(i + 1, 1, "Expected elf_id,calories"));
+        };
+
+        if i == 0 && (id.trim(), calories.trim()) == ("elf_id", "calories") {
+            continue;
+        }
+
+        let calories = calories
+            .trim()
+            .parse::<Calories>()
+            .map_err(|_| DecodeError::new(i + 1, id.len() + 2, "Expected a number of calories"))?;
+
+        let id = id.trim();
+        let position = *positions.entry(id).or_insert_with(|| {
+            elves.push(Elf {
+                index: elves.len(),
+                id: Some(id.to_string()),
+                items: Vec::new(),
+            });
+            elves.len() - 1
+        });
+
+        elves[position].items.push(calories);
+    }
+
+    Ok(elves)
+}
+
+fn decode_json(text: &str) -> Result<Vec<Elf>, DecodeError> {
+    let inventories = serde_json::from_str::<Vec<Vec<Calories>>>(text).map_err(|error| {
+        // The position is reported separately
+        let message = error.to_string();
+        let message = message.split(" at line ").next().unwrap_or_default();
+
+        DecodeError::new(error.line(), error.column(), message)
+    })?;
+
+    Ok(inventories
+        .into_iter()
+        .enumerate()
+        .map(|(index, items)| Elf {
+            index,
+            id: None,
+            items,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Elf> {
+        parse_elves(std::fs::read("example.txt").unwrap().as_slice())
+    }
+
+    #[test]
+    fn test_formats_decode_the_same_elves() {
+        let csv = "elf_id,calories\n\
+            a,1000\na,2000\na,3000\nb,4000\nc,5000\nc,6000\n\
+            d,7000\nd,8000\nd,9000\ne,10000\n";
+        let json = "[[1000, 2000, 3000], [4000], [5000, 6000], [7000, 8000, 9000], [10000]]";
+        let groups = std::fs::read("example.txt").unwrap();
+
+        assert_eq!(detect(csv).name, "CSV");
+        assert_eq!(detect(json).name, "JSON");
+        assert_eq!(detect(std::str::from_utf8(&groups).unwrap()).name, "groups");
+
+        for input in [json.as_bytes(), &groups] {
+            assert_eq!(decode_elves(input), Ok(example()));
+        }
+
+        let elves = decode_elves(csv.as_bytes()).unwrap();
+        assert_eq!(
+            elves.iter().map(|elf| &elf.items).collect::<Vec<_>>(),
+            example().iter().map(|elf| &elf.items).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_csv_interleaved_ids() {
+        let elves = decode_elves(b"7,100\n3,200\n7,300\n").unwrap();
+
+        assert_eq!(elves.len(), 2);
+        assert_eq!(elves[0].items, vec![100, 300]);
+        assert_eq!(elves[1].items, vec![200]);
+        assert_eq!(elves[1].id.as_deref(), Some("3"));
+        assert_eq!(elves[1].to_string(), "Elf 3: 200 calories in 1 items");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode_elves(b"a,1\nb,x\n"),
+            Err(DecodeError::new(2, 3, "Expected a number of calories"))
+        );
+        // Only a header naming the columns is skipped
+        assert_eq!(
+            decode_elves(b"a,x\nb,1\n"),
+            Err(DecodeError::new(1, 3, "Expected a number of calories"))
+        );
+        assert_eq!(
+            decode_elves(b"1\n\nx\n"),
+            Err(DecodeError::new(3, 1, "Expected a number of calories"))
+        );
+
+        let error = decode_elves(b"[[1, 2],\n [3, -4]]").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+    }
+}
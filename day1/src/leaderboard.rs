@@ -0,0 +1,362 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use aoc::rng::Rng;
+
+use crate::elves::{Calories, Elf, Total};
+
+/// Elves ordered as in `top_k`: most calories first, then in input order.
+type Key = (Reverse<Total>, usize);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LeaderboardError {
+    UnknownElf(usize),
+    MissingItem { elf: usize, calories: Calories },
+}
+
+impl Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeaderboardError::UnknownElf(elf) => write!(f, "There is no elf {}", elf + 1),
+            LeaderboardError::MissingItem { elf, calories } => {
+                write!(f, "Elf {} carries no {} calorie item", elf + 1, calories)
+            }
+        }
+    }
+}
+
+/// The elves ranked by the calories they carry, kept up to date as items are added and removed,
+/// so that the top elves and the rank of any elf can be found without ranking them all again.
+pub struct ElfLeaderboard {
+    elves: Vec<Elf>,
+    /// The total of each elf, adjusted by each item added or removed.
+    totals: Vec<Total>,
+    /// Where in each elf's items the items of each number of calories are.
+    positions: Vec<HashMap<Calories, Vec<usize>>>,
+    tree: RankTree,
+}
+
+impl ElfLeaderboard {
+    /// Expects the elves as parsed, each at the position given by its index.
+    pub fn new(elves: Vec<Elf>) -> Self {
+        let mut tree = RankTree::new(elves.len());
+        let totals = elves.iter().map(|elf| elf.total()).collect::<Vec<_>>();
+
+        for elf in &elves {
+            tree.insert(elf.index, (Reverse(totals[elf.index]), elf.index));
+        }
+
+        let positions = elves
+            .iter()
+            .map(|elf| {
+                let mut positions = HashMap::<_, Vec<_>>::new();
+
+                for (position, item) in elf.items.iter().enumerate() {
+                    positions.entry(*item).or_default().push(position);
+                }
+
+                positions
+            })
+            .collect();
+
+        Self {
+            elves,
+            totals,
+            positions,
+            tree,
+        }
+    }
+
+    pub fn elf(&self, index: usize) -> Option<&Elf> {
+        self.elves.get(index)
+    }
+
+    pub fn add_item(&mut self, index: usize, calories: Calories) -> Result<(), LeaderboardError> {
+        let elf = self
+            .elves
+            .get_mut(index)
+            .ok_or(LeaderboardError::UnknownElf(index))?;
+
+        self.positions[index]
+            .entry(calories)
+            .or_default()
+            .push(elf.items.len());
+        elf.items.push(calories);
+        self.totals[index] += Total::from(calories);
+        self.tree
+            .update(index, (Reverse(self.totals[index]), index));
+
+        Ok(())
+    }
+
+    /// Removes one item with the given calories from an elf. The elf's last item takes its
+    /// place.
+    pub fn remove_item(
+        &mut self,
+        index: usize,
+        calories: Calories,
+    ) -> Result<(), LeaderboardError> {
+        let elf = self
+            .elves
+            .get_mut(index)
+            .ok_or(LeaderboardError::UnknownElf(index))?;
+        let positions = &mut self.positions[index];
+        let position = positions
+            .get_mut(&calories)
+            .and_then(|positions| positions.pop())
+            .ok_or(LeaderboardError::MissingItem {
+                elf: index,
+                calories,
+            })?;
+
+        elf.items.swap_remove(position);
+
+        if let Some(moved) = elf.items.get(position) {
+            let last = elf.items.len();
+            let moved_positions = positions.get_mut(moved).unwrap();
+            let moved_position = moved_positions.iter_mut().find(|p| **p == last).unwrap();
+            *moved_position = position;
+        }
+
+        self.totals[index] -= Total::from(calories);
+        self.tree
+            .update(index, (Reverse(self.totals[index]), index));
+
+        Ok(())
+    }
+
+    /// The `k` elves carrying the most calories, in the same order as `top_k`.
+    pub fn top_k(&self, k: usize) -> Vec<&Elf> {
+        self.tree
+            .first(k)
+            .into_iter()
+            .map(|index| &self.elves[index])
+            .collect()
+    }
+
+    /// The position of an elf in the ranking, counting from 1.
+    pub fn rank(&self, index: usize) -> Result<usize, LeaderboardError> {
+        self.tree
+            .rank(index)
+            .map(|rank| rank + 1)
+            .ok_or(LeaderboardError::UnknownElf(index))
+    }
+}
+
+/// A treap holding one node per elf, in which each node knows the size of its subtree, so that
+/// ranks can be counted on the way down in logarithmic time.
+struct RankTree {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+
+#[derive(Clone)]
+struct Node {
+    key: Key,
+    priority: u64,
+    size: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl RankTree {
+    fn new(len: usize) -> Self {
+        let mut rng = Rng::new(len as u64);
+        let nodes = (0..len)
+            .map(|index| Node {
+                key: (Reverse(0), index),
+                priority: rng.next_u64(),
+                size: 1,
+                left: None,
+                right: None,
+            })
+            .collect();
+
+        Self { nodes, root: None }
+    }
+
+    fn size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |node| self.nodes[node].size)
+    }
+
+    fn resize(&mut self, node: usize) {
+        self.nodes[node].size =
+            1 + self.size(self.nodes[node].left) + self.size(self.nodes[node].right);
+    }
+
+    /// Splits a subtree into the nodes ordered before the key and the rest.
+    fn split(&mut self, node: Option<usize>, key: Key) -> (Option<usize>, Option<usize>) {
+        let Some(node) = node else {
+            return (None, None);
+        };
+
+        if self.nodes[node].key < key {
+            let (before, rest) = self.split(self.nodes[node].right, key);
+            self.nodes[node].right = before;
+            self.resize(node);
+            (Some(node), rest)
+        } else {
+            let (before, rest) = self.split(self.nodes[node].left, key);
+            self.nodes[node].left = rest;
+            self.resize(node);
+            (before, Some(node))
+        }
+    }
+
+    /// Joins two subtrees, all of the first ordered before the second.
+    fn merge(&mut self, first: Option<usize>, second: Option<usize>) -> Option<usize> {
+        match (first, second) {
+            (Some(first), Some(second)) => {
+                if self.nodes[first].priority > self.nodes[second].priority {
+                    self.nodes[first].right = self.merge(self.nodes[first].right, Some(second));
+                    self.resize(first);
+                    Some(first)
+                } else {
+                    self.nodes[second].left = self.merge(Some(first), self.nodes[second].left);
+                    self.resize(second);
+                    Some(second)
+                }
+            }
+            (node, None) | (None, node) => node,
+        }
+    }
+
+    fn insert(&mut self, node: usize, key: Key) {
+        self.nodes[node] = Node {
+            key,
+            size: 1,
+            left: None,
+            right: None,
+            ..self.nodes[node]
+        };
+
+        let (before, rest) = self.split(self.root, key);
+        let before = self.merge(before, Some(node));
+        self.root = self.merge(before, rest);
+    }
+
+    fn update(&mut self, node: usize, key: Key) {
+        let (Reverse(total), index) = self.nodes[node].key;
+        let (before, rest) = self.split(self.root, self.nodes[node].key);
+        // Indices are unique, so the node is the only one before the next index
+        let (_, after) = self.split(rest, (Reverse(total), index + 1));
+        self.root = self.merge(before, after);
+
+        self.insert(node, key);
+    }
+
+    fn rank(&self, node: usize) -> Option<usize> {
+        let key = self.nodes.get(node)?.key;
+        let mut current = self.root;
+        let mut rank = 0;
+
+        while let Some(visited) = current {
+            let visited = &self.nodes[visited];
+
+            if key < visited.key {
+                current = visited.left;
+            } else if key > visited.key {
+                rank += self.size(visited.left) + 1;
+                current = visited.right;
+            } else {
+                return Some(rank + self.size(visited.left));
+            }
+        }
+
+        None
+    }
+
+    /// The first `k` nodes in order.
+    fn first(&self, k: usize) -> Vec<usize> {
+        let mut nodes = Vec::with_capacity(k.min(self.nodes.len()));
+        let mut stack = Vec::new();
+        let mut current = self.root;
+
+        while nodes.len() < k {
+            while let Some(node) = current {
+                stack.push(node);
+                current = self.nodes[node].left;
+            }
+
+            let Some(node) = stack.pop() else {
+                break;
+            };
+
+            nodes.push(node);
+            current = self.nodes[node].right;
+        }
+
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::elves::{parse_elves, top_k};
+
+    fn indices(elves: &[&Elf]) -> Vec<usize> {
+        elves.iter().map(|elf| elf.index).collect()
+    }
+
+    #[test]
+    fn test_leaderboard_matches_top_k() {
+        let mut rng = Rng::new(7);
+        let elves = parse_elves("5\n\n4\n5\n\n7\n\n9\n\n3\n6\n\n1\n".as_bytes());
+        let mut leaderboard = ElfLeaderboard::new(elves.clone());
+        let mut expected = elves;
+
+        for _ in 0..200 {
+            let index = rng.below(expected.len() as u64) as usize;
+
+            if rng.below(2) == 0 || expected[index].items.is_empty() {
+                let calories = rng.below(10);
+                leaderboard.add_item(index, calories).unwrap();
+                expected[index].items.push(calories);
+            } else {
+                let calories = expected[index].items[0];
+                leaderboard.remove_item(index, calories).unwrap();
+                expected[index].items.swap_remove(0);
+            }
+
+            let mut items = leaderboard.elf(index).unwrap().items.clone();
+            let mut expected_items = expected[index].items.clone();
+            items.sort_unstable();
+            expected_items.sort_unstable();
+            assert_eq!(items, expected_items);
+
+            let ranked = top_k(&expected, expected.len()).top;
+
+            for k in 0..=expected.len() {
+                assert_eq!(indices(&leaderboard.top_k(k)), indices(&ranked[..k]));
+            }
+
+            for (rank, elf) in ranked.iter().enumerate() {
+                assert_eq!(leaderboard.rank(elf.index), Ok(rank + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_leaderboard_errors() {
+        let mut leaderboard = ElfLeaderboard::new(parse_elves("5\n\n4\n".as_bytes()));
+
+        assert_eq!(
+            leaderboard.add_item(2, 1),
+            Err(LeaderboardError::UnknownElf(2))
+        );
+        assert_eq!(
+            leaderboard.remove_item(1, 5),
+            Err(LeaderboardError::MissingItem {
+                elf: 1,
+                calories: 5
+            })
+        );
+        assert_eq!(leaderboard.rank(2), Err(LeaderboardError::UnknownElf(2)));
+        assert_eq!(leaderboard.top_k(5).len(), 2);
+    }
+}
@@ -1,60 +1,388 @@
+mod elves;
+mod formats;
+mod leaderboard;
+mod redistribute;
+mod stats;
+mod subset_sum;
+
 use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+    io::{self, BufRead},
+    str::FromStr,
+};
+
+use aoc::{
+    check::{check_number, check_trailing_whitespace},
+    rng::Rng,
+    Day, Line, Part, Violation,
 };
+use elves::{parse_elves, top_k, top_k_streaming, top_k_totals, Calories, Elf, ElfTotal, Total};
+use formats::{decode_elves, Decoder, GROUPS};
+use leaderboard::ElfLeaderboard;
+use stats::Stats;
+use subset_sum::subset_sum;
+
+const BENCHMARK_SIZES: &[usize] = &[1_000, 10_000, 100_000];
 
 fn main() {
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
+    Day::new()
+        .fallible_solution(Part::One, "top k", |input| {
+            solve_part_one(input).map(|total| total.to_string())
+        })
+        .fallible_solution(Part::One, "streaming heap", |input| {
+            Ok(sum_totals(&streaming_top_k(input, 1)?).to_string())
+        })
+        .fallible_solution(Part::Two, "top k", |input| {
+            solve_part_two(input).map(|total| total.to_string())
+        })
+        .fallible_solution(Part::Two, "streaming heap", |input| {
+            Ok(sum_totals(&streaming_top_k(input, 3)?).to_string())
+        })
+        .grammar(check_input)
+        .benchmark("top 3 (sort)", BENCHMARK_SIZES, |bencher, size| {
+            let input = generate_input(size);
+            bencher.iter(|| top_k(&parse_elves(input.as_bytes()), 3).total());
+        })
+        .benchmark(
+            "top 3 (streaming heap)",
+            BENCHMARK_SIZES,
+            |bencher, size| {
+                let input = generate_input(size);
+                bencher.iter(|| sum_totals(&top_k_streaming(input.as_bytes(), 3).unwrap()));
+            },
+        )
+        .command("top", "K", print_top_k)
+        .streaming_command("top-stream", "K", print_top_k_streaming)
+        .command("stats", "", print_stats)
+        .command("redistribute", "", print_redistribution)
+        .command("leaderboard", "", run_leaderboard)
+        .command("subset", "TARGET [ELF]", print_subset)
+        .run();
+}
+
+fn solve_part_one<R: BufRead>(input: R) -> Result<Total, String> {
+    Ok(top_k(&read_elves(input)?, 1).total())
+}
+
+fn solve_part_two<R: BufRead>(input: R) -> Result<Total, String> {
+    Ok(top_k(&read_elves(input)?, 3).total())
+}
+
+fn read_elves<R: BufRead>(mut input: R) -> Result<Vec<Elf>, String> {
+    let mut bytes = Vec::new();
+    input
+        .read_to_end(&mut bytes)
+        .map_err(|error| error.to_string())?;
+
+    decode_elves(&bytes).map_err(|error| format!("Could not read the elves: {}", error))
+}
+
+/// Streams inventories in the puzzle's format through the heap, but has to decode the others
+/// first.
+fn streaming_top_k<R: BufRead>(mut input: R, k: usize) -> Result<Vec<ElfTotal>, String> {
+    let start = input.fill_buf().map_err(|error| error.to_string())?;
+
+    if detect_format(start)?.name == GROUPS.name {
+        top_k_streaming(input, k)
+    } else {
+        Ok(top_k_totals(
+            read_elves(input)?.iter().map(ElfTotal::from),
+            k,
+        ))
+    }
+}
+
+/// Finds the format of an input from its start, which may end partway through a character.
+fn detect_format(start: &[u8]) -> Result<&'static Decoder, String> {
+    let text = match std::str::from_utf8(start) {
+        Ok(text) => text,
+        Err(error) if error.error_len().is_none() => {
+            std::str::from_utf8(&start[..error.valid_up_to()]).unwrap()
+        }
+        Err(_) => return Err("Input is not valid UTF-8".to_string()),
+    };
+
+    Ok(formats::detect(text))
+}
+
+fn sum_totals(elves: &[ElfTotal]) -> Total {
+    elves.iter().map(|elf| elf.total).sum()
+}
+
+fn print_top_k(input: &[u8], args: &[String]) -> Result<(), String> {
+    let k = parse_k(args)?;
+    let elves = decode_elves(input).map_err(|error| error.to_string())?;
+    let ranking = top_k(&elves, k);
+
+    for elf in &ranking.top {
+        println!("{}", elf);
+    }
+
+    for elf in &ranking.tied {
+        println!("Tied with the last: {}", elf);
+    }
+
+    println!("Total: {}", ranking.total());
+
+    Ok(())
+}
+
+/// Finds the top elves of an inventory of any size in constant memory, e.g.
+/// `generate | day1 top-stream 3 --input -`. Only the puzzle's format can be streamed.
+fn print_top_k_streaming(input: &mut dyn BufRead, args: &[String]) -> Result<(), String> {
+    let k = parse_k(args)?;
+
+    if detect_format(input.fill_buf().map_err(|error| error.to_string())?)?.name != GROUPS.name {
+        return Err("Only blank line separated inventories can be streamed".to_string());
+    }
+
+    let top = top_k_streaming(input, k)?;
+
+    for elf in &top {
+        println!("Elf {}: {} calories", elf.index + 1, elf.total);
+    }
+
+    println!("Total: {}", sum_totals(&top));
+
+    Ok(())
+}
+
+fn print_stats(input: &[u8], args: &[String]) -> Result<(), String> {
+    if !args.is_empty() {
+        return Err("Expected no arguments".to_string());
+    }
+
+    let elves = decode_elves(input).map_err(|error| error.to_string())?;
+    let stats = Stats::from_elves(&elves).ok_or("Found no elves")?;
+    print!("{}", stats);
+
+    Ok(())
+}
+
+fn print_redistribution(input: &[u8], args: &[String]) -> Result<(), String> {
+    if !args.is_empty() {
+        return Err("Expected no arguments".to_string());
+    }
+
+    let elves = decode_elves(input).map_err(|error| error.to_string())?;
+    let plan = redistribute::plan(&elves);
+    let before = elves.iter().map(|elf| elf.total()).max().unwrap_or(0);
+
+    println!(
+        "Most calories carried: {} -> {} ({})",
+        before,
+        plan.max_total(),
+        plan.method
+    );
+
+    for step in &plan.moves {
+        println!("{}", step);
+    }
+
+    for elf in &plan.elves {
+        println!("{}", elf);
+    }
 
-    let answer_one = solve_part_one(reader);
-    println!("Answer 1: {}", answer_one);
+    Ok(())
+}
+
+/// Finds elves carrying exactly the target number of calories between them, or with an elf
+/// given, items of that elf adding up to the target.
+fn print_subset(input: &[u8], args: &[String]) -> Result<(), String> {
+    let elves = decode_elves(input).map_err(|error| error.to_string())?;
+    let (target, elf) = match args {
+        [target] => (target, None),
+        [target, elf] => (target, Some(elf)),
+        _ => return Err("Expected the target number of calories".to_string()),
+    };
+    let target = target
+        .parse::<Total>()
+        .map_err(|_| format!("Expected the target number of calories, got '{}'", target))?;
 
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
+    match elf {
+        None => {
+            let totals = elves.iter().map(|elf| elf.total()).collect::<Vec<_>>();
+
+            match subset_sum(&totals, target).map_err(|error| error.to_string())? {
+                Some(subset) => {
+                    for position in subset {
+                        println!("{}", elves[position]);
+                    }
+                }
+                None => println!("No elves carry exactly {} calories together", target),
+            }
+        }
+        Some(elf) => {
+            let elf = elf
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| elves.get(number.checked_sub(1)?))
+                .ok_or_else(|| format!("There is no elf {}", elf))?;
+            let items = elf
+                .items
+                .iter()
+                .map(|item| Total::from(*item))
+                .collect::<Vec<_>>();
 
-    let answer_two = solve_part_two(reader);
-    println!("Answer 2: {}", answer_two);
+            match subset_sum(&items, target).map_err(|error| error.to_string())? {
+                Some(subset) => {
+                    for position in subset {
+                        println!("Item {}: {} calories", position + 1, elf.items[position]);
+                    }
+                }
+                None => println!(
+                    "No items of elf {} add up to exactly {} calories",
+                    elf.index + 1,
+                    target
+                ),
+            }
+        }
+    }
+
+    Ok(())
 }
 
-fn solve_part_one<R: BufRead>(input: R) -> usize {
-    *input
-        .lines()
-        .fold(vec![0], |mut sums, line| {
-            let line = line.unwrap();
+/// Keeps a leaderboard of the inventory, applying the updates and answering the queries read
+/// from stdin, one per line: `add ELF CALORIES`, `remove ELF CALORIES`, `top K` or `rank ELF`.
+fn run_leaderboard(input: &[u8], args: &[String]) -> Result<(), String> {
+    if !args.is_empty() {
+        return Err("Expected no arguments".to_string());
+    }
+
+    let elves = decode_elves(input).map_err(|error| error.to_string())?;
+    let mut leaderboard = ElfLeaderboard::new(elves);
+
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|error| error.to_string())?;
+
+        if let Err(error) = update_leaderboard(&mut leaderboard, &line) {
+            eprintln!("{}", error);
+        }
+    }
 
-            if line.is_empty() {
-                sums.push(0);
-            } else {
-                let last_index = sums.len() - 1;
-                let updated_sum = sums[last_index] + line.parse::<usize>().unwrap();
-                sums[last_index] = updated_sum;
+    Ok(())
+}
+
+fn update_leaderboard(leaderboard: &mut ElfLeaderboard, line: &str) -> Result<(), String> {
+    fn number<T: FromStr>(word: &str) -> Result<T, String> {
+        word.parse::<T>()
+            .map_err(|_| format!("Expected a number, got '{}'", word))
+    }
+
+    // Elves are numbered from 1, as they are printed
+    let elf = |word: &str| match number::<usize>(word)? {
+        0 => Err("Elves are numbered from 1".to_string()),
+        elf => Ok(elf - 1),
+    };
+
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["add", index, calories] => leaderboard
+            .add_item(elf(index)?, number::<Calories>(calories)?)
+            .map_err(|error| error.to_string()),
+        ["remove", index, calories] => leaderboard
+            .remove_item(elf(index)?, number::<Calories>(calories)?)
+            .map_err(|error| error.to_string()),
+        ["top", k] => {
+            for elf in leaderboard.top_k(number(k)?) {
+                println!("{}", elf);
             }
+            Ok(())
+        }
+        ["rank", index] => {
+            let index = elf(index)?;
+            let rank = leaderboard.rank(index).map_err(|error| error.to_string())?;
+            println!("{}, ranked {}", leaderboard.elf(index).unwrap(), rank);
+            Ok(())
+        }
+        [] => Ok(()),
+        _ => Err(format!("Unknown update or query '{}'", line)),
+    }
+}
 
-            sums
-        })
-        .iter()
-        .max()
-        .unwrap()
+fn parse_k(args: &[String]) -> Result<usize, String> {
+    match args {
+        [k] => k
+            .parse::<usize>()
+            .map_err(|_| format!("Expected the number of elves, got '{}'", k)),
+        _ => Err("Expected the number of elves".to_string()),
+    }
 }
 
-fn solve_part_two<R: BufRead>(input: R) -> usize {
-    let mut cals = input.lines().fold(vec![0], |mut sums, line| {
-        let line = line.unwrap();
+/// Generates an inventory of `elf_count` elves carrying 1 to 5 items each.
+fn generate_input(elf_count: usize) -> String {
+    let mut rng = Rng::new(1);
+    let mut input = String::new();
 
-        if line.is_empty() {
-            sums.push(0);
-        } else {
-            let last_index = sums.len() - 1;
-            sums[last_index] = sums.last().unwrap() + line.parse::<usize>().unwrap();
+    for i in 0..elf_count {
+        if i > 0 {
+            input.push('\n');
         }
 
-        sums
-    });
+        for _ in 0..=rng.below(5) {
+            input += &format!("{}\n", 1_000 + rng.below(59_000));
+        }
+    }
 
-    cals.sort_by(|cal1, cal2| cal2.cmp(cal1));
+    input
+}
+
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    let text = lines
+        .iter()
+        .map(|line| line.text)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let decoder = formats::detect(&text);
+
+    // Other formats are only checked by decoding them
+    if decoder.name != GROUPS.name {
+        let Err(error) = (decoder.decode)(&text) else {
+            return Vec::new();
+        };
+        let line = lines
+            .iter()
+            .find(|line| line.number == error.line)
+            .or(lines.last());
+
+        return line
+            .map(|line| {
+                Violation::new(
+                    line,
+                    error.column,
+                    &error.message,
+                    &format!("fix the {} syntax", decoder.name),
+                )
+            })
+            .into_iter()
+            .collect();
+    }
+
+    let mut violations = Vec::new();
 
-    cals.iter().take(3).sum::<usize>()
+    for (i, line) in lines.iter().enumerate() {
+        if line.text.is_empty() {
+            if i == 0 || lines[i - 1].text.is_empty() {
+                violations.push(Violation::new(
+                    line,
+                    1,
+                    "Blank line doesn't separate two elves",
+                    "remove the extra blank line",
+                ));
+            }
+        } else if let Some(violation) = check_trailing_whitespace(line) {
+            violations.push(violation);
+        } else if let Some(violation) = check_number(line, 1, line.text) {
+            violations.push(violation);
+        } else if line.text.parse::<Calories>().is_err() {
+            violations.push(Violation::new(
+                line,
+                1,
+                "Item has more calories than fit in 64 bits",
+                "split it into smaller items",
+            ));
+        }
+    }
+
+    violations
 }
 
 #[cfg(test)]
@@ -64,19 +392,69 @@ mod tests {
     use super::*;
 
     fn open_example() -> BufReader<File> {
-        let file = File::open("example.txt").unwrap();
-        BufReader::new(file)
+        BufReader::new(File::open("example.txt").unwrap())
     }
 
     #[test]
     fn test_part_one() {
         let input = open_example();
-        assert_eq!(solve_part_one(input), 24000);
+        assert_eq!(solve_part_one(input), Ok(24000));
     }
 
     #[test]
     fn test_part_two() {
         let input = open_example();
-        assert_eq!(solve_part_two(input), 45000);
+        assert_eq!(solve_part_two(input), Ok(45000));
+    }
+
+    #[test]
+    fn test_decode_errors_are_reported() {
+        assert_eq!(
+            solve_part_one(&b"1000\nlots\n"[..]),
+            Err("Could not read the elves: 2:1: Expected a number of calories".to_string())
+        );
+        assert!(streaming_top_k(&b"a,1\nb,x\n"[..], 1).is_err());
+        assert!(streaming_top_k(&b"\xff\n"[..], 1).is_err());
+    }
+
+    #[test]
+    fn test_streaming_top_k_formats() {
+        let json = b"[[1000, 2000, 3000], [4000], [5000, 6000], [7000, 8000, 9000], [10000]]";
+
+        assert_eq!(
+            sum_totals(&streaming_top_k(open_example(), 3).unwrap()),
+            45000
+        );
+        assert_eq!(sum_totals(&streaming_top_k(&json[..], 3).unwrap()), 45000);
+    }
+
+    #[test]
+    fn test_part_two_beyond_u64() {
+        let max = Calories::MAX;
+        let input = format!("{}\n\n{}\n1\n\n{}\n\n2\n", max, max, max);
+        assert_eq!(
+            solve_part_two(input.as_bytes()),
+            Ok(Total::from(max) * 3 + 1)
+        );
+    }
+
+    #[test]
+    fn test_check_input() {
+        let example = std::fs::read("example.txt").unwrap();
+        assert!(aoc::check::check(&example, Some(check_input)).is_empty());
+
+        let violations = aoc::check::check(b"1000\n\n\n20x0 \n", Some(check_input));
+        assert_eq!(violations.len(), 2);
+        assert_eq!((violations[0].line, violations[0].column), (3, 1));
+        assert_eq!((violations[1].line, violations[1].column), (4, 5));
+
+        let violations = aoc::check::check(b"18446744073709551616\n", Some(check_input));
+        assert_eq!(violations.len(), 1);
+
+        assert!(aoc::check::check(b"a,1\nb,2\n", Some(check_input)).is_empty());
+
+        let violations = aoc::check::check(b"a,1\nb,two\n", Some(check_input));
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].line, violations[0].column), (2, 3));
     }
 }
@@ -0,0 +1,399 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::{self, Display},
+};
+
+use crate::elves::{Calories, Elf, Total};
+
+/// Inputs with at most this many items are partitioned optimally, larger ones by heuristics.
+const EXACT_ITEM_LIMIT: usize = 16;
+/// Karmarkar–Karp keeps a partial partition of every elf for each item, so it's only tried while
+/// items × elves stays below this.
+const KARMARKAR_KARP_CELL_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Exact,
+    /// Longest processing time first: each item, largest first, goes to the lightest elf.
+    Lpt,
+    /// Largest differencing: repeatedly merges the two partial partitions that are the most
+    /// unbalanced, pairing the heaviest elf of one with the lightest of the other.
+    KarmarkarKarp,
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Exact => write!(f, "exact"),
+            Method::Lpt => write!(f, "LPT heuristic"),
+            Method::KarmarkarKarp => write!(f, "Karmarkar-Karp heuristic"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub calories: Calories,
+    /// Indices of the elves, as in `Elf::index`.
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Move {} calories from elf {} to elf {}",
+            self.calories,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
+
+/// A reassignment of items that keeps the most calories any one elf carries as low as possible.
+#[derive(Debug)]
+pub struct Plan {
+    pub method: Method,
+    /// The elves after the moves, in the same order as before.
+    pub elves: Vec<Elf>,
+    pub moves: Vec<Move>,
+}
+
+impl Plan {
+    pub fn max_total(&self) -> Total {
+        self.elves.iter().map(|elf| elf.total()).max().unwrap_or(0)
+    }
+}
+
+/// Plans how to move items between the elves so that the elf carrying the most calories carries
+/// as little as possible. Each elf ends up with one set of the partition, chosen so that as few
+/// items as possible change hands.
+pub fn plan(elves: &[Elf]) -> Plan {
+    let (calories, owners): (Vec<_>, Vec<_>) = elves
+        .iter()
+        .enumerate()
+        .flat_map(|(position, elf)| elf.items.iter().map(move |item| (*item, position)))
+        .unzip();
+    let bins = elves.len();
+
+    let (method, assignment) = if calories.len() <= EXACT_ITEM_LIMIT {
+        (Method::Exact, partition_exact(&calories, bins))
+    } else {
+        let lpt = partition_lpt(&calories, bins);
+
+        if calories.len() * bins <= KARMARKAR_KARP_CELL_LIMIT {
+            let karmarkar_karp = partition_karmarkar_karp(&calories, bins);
+
+            if largest_bin(&calories, &karmarkar_karp, bins) < largest_bin(&calories, &lpt, bins) {
+                (Method::KarmarkarKarp, karmarkar_karp)
+            } else {
+                (Method::Lpt, lpt)
+            }
+        } else {
+            (Method::Lpt, lpt)
+        }
+    };
+
+    let receivers = match_bins_to_elves(&owners, &assignment, bins);
+    let mut planned = elves
+        .iter()
+        .map(|elf| Elf {
+            index: elf.index,
+            id: elf.id.clone(),
+            items: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+    let mut moves = Vec::new();
+
+    for (item, bin) in assignment.iter().enumerate() {
+        let (from, to) = (owners[item], receivers[*bin]);
+        planned[to].items.push(calories[item]);
+
+        if from != to {
+            moves.push(Move {
+                calories: calories[item],
+                from: elves[from].index,
+                to: elves[to].index,
+            });
+        }
+    }
+
+    Plan {
+        method,
+        elves: planned,
+        moves,
+    }
+}
+
+fn largest_bin(calories: &[Calories], assignment: &[usize], bins: usize) -> Total {
+    let mut loads = vec![0; bins];
+
+    for (item, bin) in assignment.iter().enumerate() {
+        loads[*bin] += Total::from(calories[item]);
+    }
+
+    loads.into_iter().max().unwrap_or(0)
+}
+
+/// Branch and bound over the items, largest first, starting from the LPT partition. Bins with
+/// the same load are interchangeable, so an item is only tried in the first of them.
+fn partition_exact(calories: &[Calories], bins: usize) -> Vec<usize> {
+    struct Search<'a> {
+        calories: &'a [Calories],
+        order: Vec<usize>,
+        loads: Vec<Total>,
+        current: Vec<usize>,
+        best: Vec<usize>,
+        best_max: Total,
+        lower_bound: Total,
+    }
+
+    impl Search<'_> {
+        fn search(&mut self, depth: usize) {
+            if self.best_max == self.lower_bound {
+                return;
+            }
+
+            let Some(&item) = self.order.get(depth) else {
+                // Anything that got this far beats the best so far, or it would have been pruned
+                self.best_max = *self.loads.iter().max().unwrap();
+                self.best.clone_from(&self.current);
+                return;
+            };
+
+            let calories = Total::from(self.calories[item]);
+
+            for bin in 0..self.loads.len() {
+                let load = self.loads[bin];
+
+                if load + calories >= self.best_max || self.loads[..bin].contains(&load) {
+                    continue;
+                }
+
+                self.loads[bin] += calories;
+                self.current[item] = bin;
+                self.search(depth + 1);
+                self.loads[bin] -= calories;
+            }
+        }
+    }
+
+    let best = partition_lpt(calories, bins);
+    let total = calories
+        .iter()
+        .map(|item| Total::from(*item))
+        .sum::<Total>();
+    let mut order = (0..calories.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&item| Reverse(calories[item]));
+
+    let mut search = Search {
+        calories,
+        order,
+        loads: vec![0; bins],
+        current: vec![0; calories.len()],
+        best_max: largest_bin(calories, &best, bins),
+        best,
+        lower_bound: total
+            .div_ceil(bins.max(1) as Total)
+            .max(calories.iter().copied().max().map_or(0, Total::from)),
+    };
+    search.search(0);
+
+    search.best
+}
+
+fn partition_lpt(calories: &[Calories], bins: usize) -> Vec<usize> {
+    let mut order = (0..calories.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&item| Reverse(calories[item]));
+
+    let mut loads = (0..bins)
+        .map(|bin| Reverse((0 as Total, bin)))
+        .collect::<BinaryHeap<_>>();
+    let mut assignment = vec![0; calories.len()];
+
+    for item in order {
+        let Reverse((load, bin)) = loads.pop().unwrap();
+        assignment[item] = bin;
+        loads.push(Reverse((load + Total::from(calories[item]), bin)));
+    }
+
+    assignment
+}
+
+fn partition_karmarkar_karp(calories: &[Calories], bins: usize) -> Vec<usize> {
+    /// Sets are kept as trees of the merges that built them, so merging doesn't copy items.
+    enum Node {
+        Item(usize),
+        Union(usize, usize),
+    }
+
+    // A partial partition is a set of each bin as (sum, root node), heaviest first, keyed by
+    // the difference between its heaviest and lightest set
+    let mut nodes = Vec::new();
+    let mut partitions = BinaryHeap::new();
+
+    for (item, &item_calories) in calories.iter().enumerate() {
+        let item_calories = Total::from(item_calories);
+        nodes.push(Node::Item(item));
+
+        let mut sets = vec![(0, None); bins];
+        sets[0] = (item_calories, Some(item));
+        partitions.push((item_calories, sets));
+    }
+
+    while partitions.len() > 1 {
+        let (_, heavier) = partitions.pop().unwrap();
+        let (_, lighter) = partitions.pop().unwrap();
+
+        let mut sets = heavier
+            .into_iter()
+            .zip(lighter.into_iter().rev())
+            .map(|((sum_a, root_a), (sum_b, root_b))| {
+                let root = match (root_a, root_b) {
+                    (Some(a), Some(b)) => {
+                        nodes.push(Node::Union(a, b));
+                        Some(nodes.len() - 1)
+                    }
+                    (root, None) | (None, root) => root,
+                };
+                (sum_a + sum_b, root)
+            })
+            .collect::<Vec<_>>();
+        sets.sort_by_key(|&(sum, _)| Reverse(sum));
+
+        partitions.push((sets[0].0 - sets[bins - 1].0, sets));
+    }
+
+    let mut assignment = vec![0; calories.len()];
+
+    if let Some((_, sets)) = partitions.pop() {
+        for (bin, (_, root)) in sets.into_iter().enumerate() {
+            let mut stack = root.into_iter().collect::<Vec<_>>();
+
+            while let Some(node) = stack.pop() {
+                match nodes[node] {
+                    Node::Item(item) => assignment[item] = bin,
+                    Node::Union(a, b) => stack.extend([a, b]),
+                }
+            }
+        }
+    }
+
+    assignment
+}
+
+/// Gives each bin to an elf, greedily pairing the bins and elves that share the most items so
+/// that few items have to move. Returns the position of the elf receiving each bin.
+fn match_bins_to_elves(owners: &[usize], assignment: &[usize], bins: usize) -> Vec<usize> {
+    let mut shared = HashMap::<(usize, usize), usize>::new();
+
+    for (owner, bin) in owners.iter().zip(assignment) {
+        *shared.entry((*bin, *owner)).or_insert(0) += 1;
+    }
+
+    let mut pairs = shared.into_iter().collect::<Vec<_>>();
+    pairs.sort_by_key(|&(pair, count)| (Reverse(count), pair));
+
+    let mut receivers = vec![None; bins];
+    let mut matched = vec![false; bins];
+
+    for ((bin, owner), _) in pairs {
+        if receivers[bin].is_none() && !matched[owner] {
+            receivers[bin] = Some(owner);
+            matched[owner] = true;
+        }
+    }
+
+    let mut unmatched = (0..bins).filter(|elf| !matched[*elf]);
+
+    receivers
+        .into_iter()
+        .map(|receiver| receiver.unwrap_or_else(|| unmatched.next().unwrap()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::elves::parse_elves;
+
+    fn bin_loads(calories: &[Calories], assignment: &[usize], bins: usize) -> Vec<Total> {
+        let mut loads = vec![0; bins];
+
+        for (item, bin) in assignment.iter().enumerate() {
+            loads[*bin] += Total::from(calories[item]);
+        }
+
+        loads.sort_unstable();
+        loads
+    }
+
+    #[test]
+    fn test_plan_example() {
+        let elves = parse_elves(std::fs::read("example.txt").unwrap().as_slice());
+        let plan = plan(&elves);
+
+        assert_eq!(plan.method, Method::Exact);
+        assert_eq!(plan.max_total(), 11000);
+        assert_eq!(plan.elves.len(), elves.len());
+
+        // Applying the moves to the original inventories gives the planned ones
+        let mut totals = elves.iter().map(|elf| elf.total()).collect::<Vec<_>>();
+
+        for step in &plan.moves {
+            totals[step.from] -= Total::from(step.calories);
+            totals[step.to] += Total::from(step.calories);
+        }
+
+        let planned = plan.elves.iter().map(|elf| elf.total()).collect::<Vec<_>>();
+        assert_eq!(totals, planned);
+    }
+
+    #[test]
+    fn test_plan_balanced() {
+        let elves = parse_elves("5\n\n2\n3\n\n4\n1\n".as_bytes());
+        let plan = plan(&elves);
+
+        assert_eq!(plan.max_total(), 5);
+        assert!(plan.moves.is_empty());
+    }
+
+    #[test]
+    fn test_partitions() {
+        let calories = [8, 7, 6, 5, 4];
+
+        assert_eq!(
+            bin_loads(&calories, &partition_exact(&calories, 2), 2),
+            vec![15, 15]
+        );
+        assert_eq!(
+            bin_loads(&calories, &partition_karmarkar_karp(&calories, 2), 2),
+            vec![14, 16]
+        );
+        assert_eq!(
+            bin_loads(&calories, &partition_lpt(&calories, 2), 2),
+            vec![13, 17]
+        );
+        // 10 each is impossible, as 8 can't be made up to 10
+        assert_eq!(
+            largest_bin(&calories, &partition_exact(&calories, 3), 3),
+            11
+        );
+    }
+
+    #[test]
+    fn test_plan_heuristic() {
+        let input = (1..=40)
+            .map(|i| format!("{}\n{}\n", i * 100, 4100 - i * 100))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let plan = plan(&parse_elves(input.as_bytes()));
+
+        assert_ne!(plan.method, Method::Exact);
+        assert_eq!(plan.max_total(), 4100);
+        assert!(plan.moves.is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use crate::elves::{Elf, Total};
+
+const PERCENTILES: [f64; 5] = [10.0, 25.0, 75.0, 90.0, 99.0];
+const HISTOGRAM_BINS: usize = 10;
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Descriptive statistics of the calories the elves carry.
+#[derive(Debug)]
+pub struct Stats {
+    /// Every elf's total, lowest first.
+    totals: Vec<Total>,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    /// Number of elves carrying each number of items.
+    pub item_counts: BTreeMap<usize, usize>,
+}
+
+impl Stats {
+    /// Returns `None` if there are no elves to describe.
+    pub fn from_elves(elves: &[Elf]) -> Option<Self> {
+        if elves.is_empty() {
+            return None;
+        }
+
+        let mut totals = elves.iter().map(|elf| elf.total()).collect::<Vec<_>>();
+        totals.sort_unstable();
+
+        let count = totals.len() as f64;
+        let mean = totals.iter().map(|total| *total as f64).sum::<f64>() / count;
+        let variance = totals
+            .iter()
+            .map(|total| (*total as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+
+        let mut item_counts = BTreeMap::new();
+
+        for elf in elves {
+            *item_counts.entry(elf.items.len()).or_insert(0) += 1;
+        }
+
+        Some(Self {
+            median: percentile(&totals, 50.0),
+            totals,
+            mean,
+            std_dev: variance.sqrt(),
+            item_counts,
+        })
+    }
+
+    pub fn elf_count(&self) -> usize {
+        self.totals.len()
+    }
+
+    pub fn min(&self) -> Total {
+        self.totals[0]
+    }
+
+    pub fn max(&self) -> Total {
+        self.totals[self.totals.len() - 1]
+    }
+
+    pub fn percentile(&self, p: f64) -> f64 {
+        percentile(&self.totals, p)
+    }
+
+    /// Counts the elves in equally wide ranges of totals, from the lowest to the highest total.
+    pub fn histogram(&self, bins: usize) -> Vec<(Total, Total, usize)> {
+        let bin_width = (self.max() - self.min()) / bins as Total + 1;
+
+        (0..bins)
+            .map(|bin| {
+                let from = self.min() + bin as Total * bin_width;
+                let to = from + bin_width - 1;
+                let count = self
+                    .totals
+                    .iter()
+                    .filter(|total| (from..=to).contains(*total))
+                    .count();
+
+                (from, to, count)
+            })
+            .collect()
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Elves:   {}", self.elf_count())?;
+        writeln!(f, "Min:     {}", self.min())?;
+        writeln!(f, "Max:     {}", self.max())?;
+        writeln!(f, "Mean:    {:.1}", self.mean)?;
+        writeln!(f, "Median:  {:.1}", self.median)?;
+        writeln!(f, "Std dev: {:.1}", self.std_dev)?;
+
+        for p in PERCENTILES {
+            writeln!(f, "P{:<6} {:.1}", p, self.percentile(p))?;
+        }
+
+        writeln!(f, "\nItems carried:")?;
+
+        for (items, elves) in &self.item_counts {
+            writeln!(f, "  {:>3} items: {} elves", items, elves)?;
+        }
+
+        writeln!(f, "\nCalories carried:")?;
+
+        let histogram = self.histogram(HISTOGRAM_BINS);
+        let largest_bin = histogram.iter().map(|bin| bin.2).max().unwrap_or(0).max(1);
+
+        for (from, to, count) in histogram {
+            let bar = "#".repeat(count * HISTOGRAM_WIDTH / largest_bin);
+            let row = format!("  {:>8} - {:>8} | {:<6} {}", from, to, count, bar);
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the `p`th percentile of sorted values, interpolating between the closest two.
+fn percentile(sorted: &[Total], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let below = rank.floor() as usize;
+    let above = rank.ceil() as usize;
+    let fraction = rank - below as f64;
+
+    sorted[below] as f64 + (sorted[above] as f64 - sorted[below] as f64) * fraction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::elves::parse_elves;
+
+    fn example_stats() -> Stats {
+        let elves = parse_elves(std::fs::read("example.txt").unwrap().as_slice());
+        Stats::from_elves(&elves).unwrap()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = example_stats();
+
+        assert_eq!(stats.elf_count(), 5);
+        assert_eq!((stats.min(), stats.max()), (4000, 24000));
+        assert_eq!(stats.mean, 11000.0);
+        assert_eq!(stats.median, 10000.0);
+        assert!((stats.std_dev - 48_800_000_f64.sqrt()).abs() < 1e-6);
+        assert_eq!(stats.percentile(25.0), 6000.0);
+        assert_eq!(stats.percentile(90.0), 18800.0);
+        assert_eq!(
+            stats.item_counts.into_iter().collect::<Vec<_>>(),
+            vec![(1, 2), (2, 1), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        let histogram = example_stats().histogram(4);
+
+        assert_eq!(
+            histogram,
+            vec![
+                (4000, 9000, 2),
+                (9001, 14001, 2),
+                (14002, 19002, 0),
+                (19003, 24003, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_elves() {
+        assert!(Stats::from_elves(&[]).is_none());
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt::{self, Display};
+
+use crate::elves::Total;
+
+/// Targets up to this are searched by dynamic programming, which keeps an entry for every sum.
+const DYNAMIC_PROGRAMMING_TARGET_LIMIT: Total = 1 << 22;
+/// Meeting in the middle lists every subset of each half, so it's limited to this many values.
+const MEET_IN_THE_MIDDLE_VALUE_LIMIT: usize = 40;
+
+/// Marks a sum that no subset of the values seen so far adds up to.
+const UNREACHED: u32 = u32::MAX;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TooManyValues(pub usize);
+
+impl Display for TooManyValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The target is too large to search {} values for, try at most {}",
+            self.0, MEET_IN_THE_MIDDLE_VALUE_LIMIT
+        )
+    }
+}
+
+/// Finds values that add up to exactly the target, returning their positions in ascending order,
+/// or `None` if no subset does.
+pub fn subset_sum(values: &[Total], target: Total) -> Result<Option<Vec<usize>>, TooManyValues> {
+    if target <= DYNAMIC_PROGRAMMING_TARGET_LIMIT && values.len() < UNREACHED as usize {
+        Ok(subset_sum_dynamic_programming(values, target))
+    } else if values.len() <= MEET_IN_THE_MIDDLE_VALUE_LIMIT {
+        Ok(subset_sum_meet_in_the_middle(values, target))
+    } else {
+        Err(TooManyValues(values.len()))
+    }
+}
+
+/// Goes through the values, marking every sum that becomes reachable with the value that first
+/// reached it. Sums are visited from the highest down, so each value is used at most once, and
+/// following the marks back from the target only ever uses earlier values.
+fn subset_sum_dynamic_programming(values: &[Total], target: Total) -> Option<Vec<usize>> {
+    let target = target as usize;
+    let mut reached_by = vec![UNREACHED; target + 1];
+    let reached = |reached_by: &[u32], sum: usize| sum == 0 || reached_by[sum] != UNREACHED;
+
+    for (position, &value) in values.iter().enumerate() {
+        if value == 0 || value > target as Total {
+            continue;
+        }
+
+        let value = value as usize;
+
+        for sum in (value..=target).rev() {
+            if reached_by[sum] == UNREACHED && reached(&reached_by, sum - value) {
+                reached_by[sum] = position as u32;
+            }
+        }
+
+        if reached(&reached_by, target) {
+            break;
+        }
+    }
+
+    if !reached(&reached_by, target) {
+        return None;
+    }
+
+    let mut subset = Vec::new();
+    let mut sum = target;
+
+    while sum > 0 {
+        let position = reached_by[sum] as usize;
+        subset.push(position);
+        sum -= values[position] as usize;
+    }
+
+    subset.reverse();
+    Some(subset)
+}
+
+/// Lists the sums of every subset of each half of the values, then looks for a sum of the first
+/// half whose complement is among the sorted sums of the second.
+fn subset_sum_meet_in_the_middle(values: &[Total], target: Total) -> Option<Vec<usize>> {
+    let (first, second) = values.split_at(values.len() / 2);
+    let first_sums = subset_sums(first);
+    let mut second_sums = subset_sums(second);
+    second_sums.sort_unstable();
+
+    first_sums.into_iter().find_map(|(sum, first_mask)| {
+        let rest = target.checked_sub(sum)?;
+        let found = second_sums.partition_point(|(sum, _)| *sum < rest);
+        let &(second_sum, second_mask) = second_sums.get(found)?;
+
+        (second_sum == rest).then(|| {
+            let mut subset = positions(first_mask, 0);
+            subset.extend(positions(second_mask, first.len()));
+            subset
+        })
+    })
+}
+
+/// The sum of each subset, with a mask of the values it's made of.
+fn subset_sums(values: &[Total]) -> Vec<(Total, u64)> {
+    let mut sums = Vec::with_capacity(1 << values.len());
+    sums.push((0, 0));
+
+    for (position, value) in values.iter().enumerate() {
+        for i in 0..sums.len() {
+            let (sum, mask) = sums[i];
+            sums.push((sum + value, mask | 1 << position));
+        }
+    }
+
+    sums
+}
+
+fn positions(mask: u64, offset: usize) -> Vec<usize> {
+    (0..u64::BITS as usize)
+        .filter(|bit| mask & 1 << bit != 0)
+        .map(|bit| offset + bit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc::rng::Rng;
+
+    use super::*;
+
+    fn sum(values: &[Total], subset: &[usize]) -> Total {
+        subset.iter().map(|position| values[*position]).sum()
+    }
+
+    #[test]
+    fn test_subset_sum() {
+        let values = [6000, 4000, 11000, 24000, 10000];
+
+        let subset = subset_sum(&values, 45000).unwrap().unwrap();
+        assert_eq!(sum(&values, &subset), 45000);
+
+        assert_eq!(subset_sum(&values, 0), Ok(Some(Vec::new())));
+        assert_eq!(subset_sum(&values, 1000), Ok(None));
+        assert_eq!(subset_sum(&values, 56000), Ok(None));
+    }
+
+    #[test]
+    fn test_methods_agree() {
+        let mut rng = Rng::new(3);
+
+        for _ in 0..100 {
+            let values = (0..rng.below(12))
+                .map(|_| Total::from(rng.below(50)))
+                .collect::<Vec<_>>();
+            let target = Total::from(rng.below(200));
+
+            let dynamic_programming = subset_sum_dynamic_programming(&values, target);
+            let meet_in_the_middle = subset_sum_meet_in_the_middle(&values, target);
+            assert_eq!(dynamic_programming.is_some(), meet_in_the_middle.is_some());
+
+            for subset in dynamic_programming.iter().chain(&meet_in_the_middle) {
+                assert_eq!(sum(&values, subset), target);
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_target() {
+        let values = (0..30)
+            .map(|i| Total::from(u64::MAX) - i * 1_000)
+            .collect::<Vec<_>>();
+        let target = values[3] + values[17] + values[29];
+
+        let subset = subset_sum(&values, target).unwrap().unwrap();
+        assert_eq!(sum(&values, &subset), target);
+
+        let values = vec![Total::from(u64::MAX); MEET_IN_THE_MIDDLE_VALUE_LIMIT + 1];
+        assert_eq!(
+            subset_sum(&values, target),
+            Err(TooManyValues(MEET_IN_THE_MIDDLE_VALUE_LIMIT + 1))
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use crate::rules::{get_round_score, Rules};
+
+/// Rounding allowed for in the simplex method's comparisons.
+const EPSILON: f64 = 1e-9;
+
+/// The best mix of shapes to play at random against an opponent out to keep my score down, as
+/// if each point I scored was theirs to lose.
+#[derive(Debug)]
+pub struct Equilibrium {
+    /// How often I play each shape.
+    pub mine: Vec<f64>,
+    /// How often the opponent plays each shape to hold me to the value.
+    pub theirs: Vec<f64>,
+    /// The score a round is worth on average when both play their mix. Neither can do better by
+    /// playing otherwise.
+    pub value: f64,
+}
+
+/// Solves the game the rules' scores define for its equilibrium, with linear programming.
+pub fn solve(rules: &Rules) -> Equilibrium {
+    let shapes = rules.game.shapes().collect::<Vec<_>>();
+    let scores = shapes
+        .iter()
+        .map(|mine| {
+            shapes
+                .iter()
+                .map(|theirs| get_round_score(rules, mine, theirs) as f64)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    solve_matrix(&scores)
+}
+
+/// Solves a zero-sum game for the player choosing the row, who scores `scores[row][column]`.
+///
+/// With every score shifted to be positive, the opponent's mix is found by maximising the sum of
+/// `z` subject to `scores * z <= 1` and `z >= 0`, and mine is the solution of its dual, read off
+/// the final tableau. The sum is the inverse of the shifted value.
+fn solve_matrix(scores: &[Vec<f64>]) -> Equilibrium {
+    let rows = scores.len();
+    let columns = scores[0].len();
+    let lowest = scores
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let shift = 1.0 - lowest;
+
+    // A row per constraint, with a slack variable each after the columns' variables and the
+    // bound last, and the objective's row at the bottom
+    let width = columns + rows + 1;
+    let mut tableau = vec![vec![0.0; width]; rows + 1];
+
+    for (row, scores) in scores.iter().enumerate() {
+        for (column, score) in scores.iter().enumerate() {
+            tableau[row][column] = score + shift;
+        }
+
+        tableau[row][columns + row] = 1.0;
+        tableau[row][width - 1] = 1.0;
+    }
+
+    tableau[rows][..columns].fill(-1.0);
+
+    // The slack variables start out as the basis
+    let mut basis = (columns..columns + rows).collect::<Vec<_>>();
+
+    // Bland's rule, entering and leaving by the lowest index, keeps the method from cycling
+    while let Some(entering) = (0..width - 1).find(|&column| tableau[rows][column] < -EPSILON) {
+        let leaving = (0..rows)
+            .filter(|&row| tableau[row][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                let ratio = |row: usize| tableau[row][width - 1] / tableau[row][entering];
+                ratio(a).total_cmp(&ratio(b)).then(basis[a].cmp(&basis[b]))
+            })
+            .expect("The scores are positive, so the program is bounded");
+
+        pivot(&mut tableau, leaving, entering);
+        basis[leaving] = entering;
+    }
+
+    let sum = tableau[rows][width - 1];
+    let mut theirs = vec![0.0; columns];
+
+    for (row, variable) in basis.iter().enumerate() {
+        if *variable < columns {
+            theirs[*variable] = tableau[row][width - 1] / sum;
+        }
+    }
+
+    let mine = (0..rows)
+        .map(|row| tableau[rows][columns + row] / sum)
+        .collect();
+
+    Equilibrium {
+        mine,
+        theirs,
+        value: 1.0 / sum - shift,
+    }
+}
+
+fn pivot(tableau: &mut [Vec<f64>], row: usize, column: usize) {
+    let divisor = tableau[row][column];
+
+    for value in tableau[row].iter_mut() {
+        *value /= divisor;
+    }
+
+    let pivot_row = tableau[row].clone();
+
+    for (other, values) in tableau.iter_mut().enumerate() {
+        let factor = values[column];
+
+        if other == row || factor == 0.0 {
+            continue;
+        }
+
+        for (value, pivot_value) in values.iter_mut().zip(&pivot_row) {
+            *value -= factor * pivot_value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::Game;
+
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} is not {}", a, b);
+    }
+
+    /// Checks neither player can do better against the other's mix.
+    fn assert_equilibrium(rules: &Rules) {
+        let equilibrium = solve(rules);
+        let shapes = rules.game.shapes().collect::<Vec<_>>();
+        let score = |mine: usize, theirs: usize| {
+            get_round_score(rules, &shapes[mine], &shapes[theirs]) as f64
+        };
+
+        assert_close(equilibrium.mine.iter().sum(), 1.0);
+        assert_close(equilibrium.theirs.iter().sum(), 1.0);
+
+        for shape in 0..shapes.len() {
+            let against_theirs = (0..shapes.len())
+                .map(|theirs| equilibrium.theirs[theirs] * score(shape, theirs))
+                .sum::<f64>();
+            let against_mine = (0..shapes.len())
+                .map(|mine| equilibrium.mine[mine] * score(mine, shape))
+                .sum::<f64>();
+
+            assert!(against_theirs <= equilibrium.value + 1e-6);
+            assert!(against_mine >= equilibrium.value - 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_rock_paper_scissors() {
+        let equilibrium = solve(&Rules::default());
+
+        for probability in &equilibrium.mine {
+            assert_close(*probability, 1.0 / 3.0);
+        }
+        // Scissors beating Paper scores the most, so Paper is played against the least
+        for (probability, expected) in equilibrium.theirs.iter().zip([4.0, 1.0, 4.0]) {
+            assert_close(*probability, expected / 9.0);
+        }
+        assert_close(equilibrium.value, 5.0);
+    }
+
+    #[test]
+    fn test_asymmetric_scores() {
+        let rules = Rules::from_toml("shape_scores = [1, 2, 30]").unwrap();
+        let equilibrium = solve(&rules);
+
+        // Scissors scores more even when it loses than anything else can, so it's always played
+        assert_close(equilibrium.mine[2], 1.0);
+        assert_close(equilibrium.theirs[0], 1.0);
+        assert_close(equilibrium.value, 30.0);
+        assert_equilibrium(&rules);
+        assert_equilibrium(&Rules::from_toml("shape_scores = [1, 2, 5]").unwrap());
+    }
+
+    #[test]
+    fn test_equilibria() {
+        assert_equilibrium(&Rules::for_game(Game::rock_paper_scissors_lizard_spock()).unwrap());
+        assert_equilibrium(
+            &Rules::from_toml("outcome_scores = { loss = 0, draw = 0, win = 1 }").unwrap(),
+        );
+        assert_close(solve_matrix(&[vec![-1.0, 1.0], vec![1.0, -1.0]]).value, 0.0);
+    }
+}
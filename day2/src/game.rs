@@ -0,0 +1,279 @@
+use std::fmt::{self, Display};
+
+/// A shape of a game, by its position in the game's list of shapes.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct Shape(pub usize);
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Win => write!(f, "win"),
+            Outcome::Draw => write!(f, "draw"),
+            Outcome::Loss => write!(f, "loss"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RulesError {
+    /// With an even number of shapes, they can't all beat as many shapes as they lose to.
+    EvenShapeCount(usize),
+    UnknownShape(String),
+    /// Neither of the two shapes beats the other.
+    Undecided(String, String),
+    /// Both shapes beat each other, or a shape beats itself.
+    Contradictory(String, String),
+    /// The shape doesn't beat exactly half of the others.
+    Unbalanced(String),
+    /// There are too many shapes to letter them, so their symbols have to be given.
+    NoDefaultSymbols(usize),
+}
+
+impl Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::EvenShapeCount(count) => {
+                write!(f, "A game needs an odd number of shapes, not {}", count)
+            }
+            RulesError::UnknownShape(name) => write!(f, "Unknown shape '{}'", name),
+            RulesError::Undecided(a, b) => write!(f, "Neither {} nor {} wins", a, b),
+            RulesError::Contradictory(a, b) => write!(f, "{} and {} both win", a, b),
+            RulesError::Unbalanced(name) => {
+                write!(f, "{} has to beat exactly half of the other shapes", name)
+            }
+            RulesError::NoDefaultSymbols(count) => write!(
+                f,
+                "There are too few letters for {} shapes, so their symbols have to be given",
+                count
+            ),
+        }
+    }
+}
+
+/// A cyclic hand game: an odd number of shapes, each beating half of the others and losing to
+/// the other half, so that any outcome can be played against any shape.
+#[derive(Debug, Clone)]
+pub struct Game {
+    names: Vec<String>,
+    /// `beats[a][b]` is whether shape `a` beats shape `b`.
+    beats: Vec<Vec<bool>>,
+}
+
+impl Game {
+    /// Builds a game from its shapes and a table of which shape beats which, given as
+    /// (winner, loser) names.
+    pub fn from_rules(names: &[&str], rules: &[(&str, &str)]) -> Result<Self, RulesError> {
+        let position = |name: &str| {
+            names
+                .iter()
+                .position(|shape| *shape == name)
+                .ok_or_else(|| RulesError::UnknownShape(name.to_string()))
+        };
+
+        let mut wins = Vec::with_capacity(rules.len());
+
+        for (winner, loser) in rules {
+            wins.push((Shape(position(winner)?), Shape(position(loser)?)));
+        }
+
+        Self::new(names.iter().map(|name| name.to_string()).collect(), &wins)
+    }
+
+    pub fn new(names: Vec<String>, wins: &[(Shape, Shape)]) -> Result<Self, RulesError> {
+        let count = names.len();
+
+        if count.is_multiple_of(2) {
+            return Err(RulesError::EvenShapeCount(count));
+        }
+
+        let mut beats = vec![vec![false; count]; count];
+
+        for (Shape(winner), Shape(loser)) in wins {
+            if winner == loser || beats[*loser][*winner] {
+                return Err(RulesError::Contradictory(
+                    names[*winner].clone(),
+                    names[*loser].clone(),
+                ));
+            }
+
+            beats[*winner][*loser] = true;
+        }
+
+        for a in 0..count {
+            for b in a + 1..count {
+                if !beats[a][b] && !beats[b][a] {
+                    return Err(RulesError::Undecided(names[a].clone(), names[b].clone()));
+                }
+            }
+
+            if beats[a].iter().filter(|beaten| **beaten).count() != count / 2 {
+                return Err(RulesError::Unbalanced(names[a].clone()));
+            }
+        }
+
+        Ok(Self { names, beats })
+    }
+
+    /// A game in which each shape beats the half of the shapes listed just before it, wrapping
+    /// around.
+    pub fn cyclic(names: &[&str]) -> Result<Self, RulesError> {
+        let count = names.len();
+        let wins = (0..count)
+            .flat_map(|winner| {
+                (1..=count / 2).map(move |distance| {
+                    (Shape(winner), Shape((winner + count - distance) % count))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Self::new(names.iter().map(|name| name.to_string()).collect(), &wins)
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Self::cyclic(&["Rock", "Paper", "Scissors"]).unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::from_rules(
+            &["Rock", "Paper", "Scissors", "Lizard", "Spock"],
+            &[
+                ("Scissors", "Paper"),
+                ("Paper", "Rock"),
+                ("Rock", "Lizard"),
+                ("Lizard", "Spock"),
+                ("Spock", "Scissors"),
+                ("Scissors", "Lizard"),
+                ("Lizard", "Paper"),
+                ("Paper", "Spock"),
+                ("Spock", "Rock"),
+                ("Rock", "Scissors"),
+            ],
+        )
+        .unwrap()
+    }
+
+    pub fn shape_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn shapes(&self) -> impl Iterator<Item = Shape> {
+        (0..self.shape_count()).map(Shape)
+    }
+
+    pub fn name(&self, shape: &Shape) -> &str {
+        &self.names[shape.0]
+    }
+
+    pub fn shape_named(&self, name: &str) -> Option<Shape> {
+        self.names.iter().position(|shape| shape == name).map(Shape)
+    }
+
+    pub fn beats(&self, shape: &Shape, other: &Shape) -> bool {
+        self.beats[shape.0][other.0]
+    }
+}
+
+/// Finds the shape to play for the outcome. When several shapes give it, the first of them in
+/// the game's list is played.
+pub fn get_shape_required_for_outcome(
+    game: &Game,
+    opponent_shape: &Shape,
+    outcome: &Outcome,
+) -> Shape {
+    game.shapes()
+        .find(|shape| get_outcome(game, shape, opponent_shape) == *outcome)
+        .unwrap()
+}
+
+pub fn get_outcome(game: &Game, my_shape: &Shape, others_shape: &Shape) -> Outcome {
+    if my_shape == others_shape {
+        Outcome::Draw
+    } else if game.beats(my_shape, others_shape) {
+        Outcome::Win
+    } else {
+        Outcome::Loss
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROCK: Shape = Shape(0);
+    const PAPER: Shape = Shape(1);
+    const SCISSORS: Shape = Shape(2);
+
+    #[test]
+    fn test_rock_paper_scissors() {
+        let game = Game::rock_paper_scissors();
+
+        assert!(game.beats(&ROCK, &SCISSORS));
+        assert!(game.beats(&PAPER, &ROCK));
+        assert!(game.beats(&SCISSORS, &PAPER));
+        assert_eq!(
+            get_shape_required_for_outcome(&game, &ROCK, &Outcome::Loss),
+            SCISSORS
+        );
+    }
+
+    #[test]
+    fn test_rock_paper_scissors_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let (lizard, spock) = (Shape(3), Shape(4));
+
+        assert_eq!(get_outcome(&game, &lizard, &spock), Outcome::Win);
+        assert_eq!(get_outcome(&game, &spock, &ROCK), Outcome::Win);
+        assert_eq!(get_outcome(&game, &ROCK, &spock), Outcome::Loss);
+
+        for opponent in game.shapes() {
+            for outcome in [Outcome::Loss, Outcome::Draw, Outcome::Win] {
+                let shape = get_shape_required_for_outcome(&game, &opponent, &outcome);
+                assert_eq!(get_outcome(&game, &shape, &opponent), outcome);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cyclic_matches_rules_table() {
+        let cyclic = Game::cyclic(&["A", "B", "C", "D", "E", "F", "G"]).unwrap();
+
+        for shape in cyclic.shapes() {
+            let beaten = cyclic
+                .shapes()
+                .filter(|other| cyclic.beats(&shape, other))
+                .count();
+            assert_eq!(beaten, 3);
+        }
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert_eq!(
+            Game::cyclic(&["Rock", "Paper"]).unwrap_err(),
+            RulesError::EvenShapeCount(2)
+        );
+        assert_eq!(
+            Game::from_rules(&["A", "B", "C"], &[("A", "B"), ("B", "C")]).unwrap_err(),
+            RulesError::Undecided("A".to_string(), "C".to_string())
+        );
+        assert_eq!(
+            Game::from_rules(&["A", "B", "C"], &[("A", "B"), ("B", "A")]).unwrap_err(),
+            RulesError::Contradictory("B".to_string(), "A".to_string())
+        );
+        assert_eq!(
+            Game::from_rules(&["A", "B", "C"], &[("A", "B"), ("A", "C"), ("B", "C")]).unwrap_err(),
+            RulesError::Unbalanced("A".to_string())
+        );
+        assert_eq!(
+            Game::from_rules(&["A", "B", "C"], &[("A", "D")]).unwrap_err(),
+            RulesError::UnknownShape("D".to_string())
+        );
+    }
+}
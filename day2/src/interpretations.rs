@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use crate::{
+    game::{get_shape_required_for_outcome, Game, Outcome, Shape},
+    rules::{get_round_score, Rules},
+};
+
+/// What the symbols of the second column stand for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mapping {
+    Shapes(Vec<(char, Shape)>),
+    Outcomes(Vec<(char, Outcome)>),
+}
+
+impl Mapping {
+    pub fn describe(&self, game: &Game) -> String {
+        let meanings = match self {
+            Mapping::Shapes(shapes) => shapes
+                .iter()
+                .map(|(symbol, shape)| format!("{}={}", symbol, game.name(shape)))
+                .collect::<Vec<_>>(),
+            Mapping::Outcomes(outcomes) => outcomes
+                .iter()
+                .map(|(symbol, outcome)| format!("{}={}", symbol, outcome))
+                .collect(),
+        };
+
+        meanings.join(" ")
+    }
+}
+
+/// Games with more shapes have too many ways of assigning my symbols to them to try each.
+const MAX_PERMUTED_SHAPES: usize = 8;
+
+#[derive(Debug)]
+pub struct Interpretation {
+    pub mapping: Mapping,
+    pub score: u64,
+}
+
+/// Scores the guide under every way of reading the second column: as each assignment of my
+/// symbols to shapes, and as each assignment of the outcome symbols to outcomes. Returns them
+/// from the highest score to the lowest. Readings whose symbols don't cover the second column
+/// are left out, and a guide that no reading covers is an error.
+pub fn interpret(rules: &Rules, input: &str) -> Result<Vec<Interpretation>, String> {
+    if rules.game.shape_count() > MAX_PERMUTED_SHAPES {
+        return Err(format!(
+            "Guides of games of more than {} shapes have too many interpretations to try",
+            MAX_PERMUTED_SHAPES
+        ));
+    }
+
+    // Rounds only differ by the opponent's shape and my symbol, so each pair is scored once
+    let mut rounds = HashMap::<(Shape, char), u64>::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let mut chars = line.chars();
+        let (Some(opponent), Some(' '), Some(symbol), None) =
+            (chars.next(), chars.next(), chars.next(), chars.next())
+        else {
+            return Err(format!(
+                "Line {}: Expected two symbols separated by a space, got '{}'",
+                i + 1,
+                line
+            ));
+        };
+        let opponent = rules
+            .opponent_shape(opponent)
+            .ok_or_else(|| format!("Line {}: Unknown opponent's symbol '{}'", i + 1, opponent))?;
+
+        *rounds.entry((opponent, symbol)).or_insert(0) += 1;
+    }
+
+    // None if a symbol has no shape
+    let score = |shape_for: &dyn Fn(Shape, char) -> Option<Shape>| {
+        rounds
+            .iter()
+            .map(|((opponent, symbol), count)| {
+                let shape = shape_for(*opponent, *symbol)?;
+                Some(count * get_round_score(rules, &shape, opponent))
+            })
+            .sum::<Option<u64>>()
+    };
+
+    let shapes = rules.game.shapes().collect::<Vec<_>>();
+    let outcomes = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
+    let as_shapes = permutations(&shapes).filter_map(|shapes| {
+        let mapping = rules
+            .my_symbols()
+            .iter()
+            .copied()
+            .zip(shapes)
+            .collect::<Vec<_>>();
+        let score = score(&|_, symbol| find(&mapping, symbol))?;
+
+        Some(Interpretation {
+            mapping: Mapping::Shapes(mapping),
+            score,
+        })
+    });
+
+    let as_outcomes = permutations(&outcomes).filter_map(|outcomes| {
+        let mapping = rules
+            .outcome_symbols()
+            .iter()
+            .copied()
+            .zip(outcomes)
+            .collect::<Vec<_>>();
+        let score = score(&|opponent, symbol| {
+            let outcome = find(&mapping, symbol)?;
+            Some(get_shape_required_for_outcome(
+                &rules.game,
+                &opponent,
+                &outcome,
+            ))
+        })?;
+
+        Some(Interpretation {
+            mapping: Mapping::Outcomes(mapping),
+            score,
+        })
+    });
+
+    let mut interpretations = as_shapes.chain(as_outcomes).collect::<Vec<_>>();
+
+    if interpretations.is_empty() {
+        return Err(
+            "The second column has symbols that are neither my shapes nor outcomes".to_string(),
+        );
+    }
+
+    interpretations.sort_by_key(|interpretation| std::cmp::Reverse(interpretation.score));
+
+    Ok(interpretations)
+}
+
+fn find<T: Copy>(mapping: &[(char, T)], symbol: char) -> Option<T> {
+    mapping
+        .iter()
+        .find(|(mapped, _)| *mapped == symbol)
+        .map(|(_, meaning)| *meaning)
+}
+
+/// The orderings of the items, generated one at a time in lexicographic order of their
+/// positions.
+fn permutations<T: Clone>(items: &[T]) -> impl Iterator<Item = Vec<T>> + '_ {
+    let mut next = Some((0..items.len()).collect::<Vec<_>>());
+
+    std::iter::from_fn(move || {
+        let mut positions = next.take()?;
+        let permutation = positions.iter().map(|i| items[*i].clone()).collect();
+
+        // The next ordering swaps the last position smaller than the one after it with the last
+        // one larger than it, then puts those after it back in order
+        if let Some(pivot) = positions.windows(2).rposition(|pair| pair[0] < pair[1]) {
+            let successor = positions
+                .iter()
+                .rposition(|position| *position > positions[pivot])
+                .unwrap();
+
+            positions.swap(pivot, successor);
+            positions[pivot + 1..].reverse();
+            next = Some(positions);
+        }
+
+        Some(permutation)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpret_example() {
+        let rules = Rules::default();
+        let example = std::fs::read_to_string("example.txt").unwrap();
+        let interpretations = interpret(&rules, &example).unwrap();
+
+        assert_eq!(interpretations.len(), 12);
+        assert_eq!(interpretations[0].score, 24);
+        assert!(interpretations
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+
+        let score_of = |mapping: Mapping| {
+            interpretations
+                .iter()
+                .find(|interpretation| interpretation.mapping == mapping)
+                .unwrap()
+                .score
+        };
+
+        let puzzle_shapes =
+            Mapping::Shapes(vec![('X', Shape(0)), ('Y', Shape(1)), ('Z', Shape(2))]);
+        let puzzle_outcomes = Mapping::Outcomes(vec![
+            ('X', Outcome::Loss),
+            ('Y', Outcome::Draw),
+            ('Z', Outcome::Win),
+        ]);
+
+        assert_eq!(score_of(puzzle_shapes.clone()), 15);
+        assert_eq!(score_of(puzzle_outcomes), 12);
+        assert_eq!(
+            puzzle_shapes.describe(&rules.game),
+            "X=Rock Y=Paper Z=Scissors"
+        );
+    }
+
+    #[test]
+    fn test_mismatched_symbols() {
+        let rules = Rules::from_toml("[symbols]\nme = 'rps'\noutcomes = 'LDW'").unwrap();
+
+        // Only reading the second column as shapes covers its symbols
+        let interpretations = interpret(&rules, "A r\nB p\n").unwrap();
+        assert_eq!(interpretations.len(), 6);
+        assert!(interpretations
+            .iter()
+            .all(|interpretation| matches!(interpretation.mapping, Mapping::Shapes(_))));
+
+        let interpretations = interpret(&rules, "A L\nB W\n").unwrap();
+        assert_eq!(interpretations.len(), 6);
+
+        assert!(interpret(&rules, "A r\nB W\n").is_err());
+        assert!(interpret(&rules, "A r\nD p\n").is_err());
+        assert!(interpret(&rules, "A r\nBp\n").is_err());
+        assert!(interpret(&rules, "A r\n\n").is_err());
+    }
+
+    #[test]
+    fn test_permutations() {
+        assert_eq!(
+            permutations(&[1, 2, 3]).collect::<Vec<_>>(),
+            vec![
+                vec![1, 2, 3],
+                vec![1, 3, 2],
+                vec![2, 1, 3],
+                vec![2, 3, 1],
+                vec![3, 1, 2],
+                vec![3, 2, 1]
+            ]
+        );
+        assert_eq!(
+            permutations::<u8>(&[]).collect::<Vec<_>>(),
+            vec![Vec::<u8>::new()]
+        );
+        assert_eq!(permutations(&[1, 1]).count(), 2);
+        assert_eq!(permutations(&[0; 5]).count(), 120);
+    }
+
+    #[test]
+    fn test_too_many_shapes() {
+        let names = (0..9).map(|i| format!("'S{}'", i)).collect::<Vec<_>>();
+        let rules = Rules::from_toml(&format!("shapes = [{}]", names.join(", "))).unwrap();
+
+        assert!(interpret(&rules, "A R\n").is_err());
+    }
+}
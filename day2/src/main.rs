@@ -1,154 +1,622 @@
+mod equilibrium;
+mod game;
+mod interpretations;
+mod network;
+mod predictor;
+mod report;
+mod reverse;
+mod rules;
+mod strategy;
+mod table;
+mod tournament;
+
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufWriter, Write},
+    net::TcpListener,
 };
 
+use aoc::{check::check_trailing_whitespace, rng::Rng, Day, Line, Part, Violation};
+use game::{get_shape_required_for_outcome, Game, Outcome, Shape};
+use interpretations::Mapping;
+use predictor::Markov;
+use report::Report;
+use reverse::Reading;
+use rules::{get_round_score, Rules};
+use strategy::{Constant, Cycle, FollowGuide, FrequencyCounter, Random, Strategy};
+use table::ScoreTable;
+use tournament::Entrant;
+
+const BENCHMARK_SIZES: &[usize] = &[1_000, 10_000, 100_000];
+/// Every match keeps the history of its rounds, so tournaments are kept from running out of
+/// memory.
+const MAX_TOURNAMENT_ROUNDS: u64 = 1_000_000;
+/// Guides are built and printed whole, so they're kept from running out of memory.
+const MAX_GUIDE_ROUNDS: usize = 10_000_000;
+
 fn main() {
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
+    Day::new()
+        .fallible_solution(Part::One, "shapes", |input| {
+            solve_part_one(input).map(|score| score.to_string())
+        })
+        .fallible_solution(Part::One, "lookup table", |input| {
+            score_with_table(input, |rules, guide| score_shapes(rules, guide))
+                .map(|score| score.to_string())
+        })
+        .fallible_solution(Part::Two, "outcomes", |input| {
+            solve_part_two(input).map(|score| score.to_string())
+        })
+        .fallible_solution(Part::Two, "lookup table", |input| {
+            score_with_table(input, |rules, guide| score_outcomes(rules, guide))
+                .map(|score| score.to_string())
+        })
+        .grammar(check_input)
+        .benchmark("score (parsing)", BENCHMARK_SIZES, |bencher, size| {
+            let input = generate_input(size);
+            bencher.iter(|| solve_part_one(input.as_bytes()));
+        })
+        .benchmark("score (lookup table)", BENCHMARK_SIZES, |bencher, size| {
+            let input = generate_input(size);
+            bencher.iter(|| {
+                score_with_table(input.as_bytes(), |rules, guide| score_shapes(rules, guide))
+            });
+        })
+        .command("score", "rps|rpsls|RULES_FILE", print_scores)
+        .command("interpret", "[rps|rpsls|RULES_FILE]", print_interpretations)
+        .command(
+            "report",
+            "shapes|outcomes [table|csv] [rps|rpsls|RULES_FILE]",
+            print_report,
+        )
+        .command("tournament", "[ROUNDS] [SEED]", print_tournament)
+        .command("predict", "[ORDER]", print_predictions)
+        .no_input_command(
+            "serve",
+            "ADDRESS ROUNDS LOG_FILE [rps|rpsls|RULES_FILE]",
+            run_server,
+        )
+        .command(
+            "play",
+            "ADDRESS STRATEGY [rps|rpsls|RULES_FILE]",
+            run_player,
+        )
+        .no_input_command(
+            "reverse",
+            "shapes|outcomes ROUNDS TARGET [rps|rpsls|RULES_FILE]",
+            print_reverse_guide,
+        )
+        .no_input_command("equilibrium", "[rps|rpsls|RULES_FILE]", print_equilibrium)
+        .run();
+}
 
-    let answer_one = solve_part_one(reader);
-    println!("Answer 1: {}", answer_one);
+fn solve_part_one<R: BufRead>(input: R) -> Result<u64, String> {
+    score_shapes(&Rules::default(), input)
+}
 
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
+fn solve_part_two<R: BufRead>(input: R) -> Result<u64, String> {
+    score_outcomes(&Rules::default(), input)
+}
 
-    let answer_two = solve_part_two(reader);
-    println!("Answer 2: {}", answer_two);
+fn score_shapes<R: BufRead>(rules: &Rules, input: R) -> Result<u64, String> {
+    total_score(rules, play_shapes(rules, input))
 }
 
-fn solve_part_one<R: BufRead>(input: R) -> u32 {
-    input
-        .lines()
-        .map(|line| {
-            let (opponent, me) = parse_line_shapes(&line.unwrap());
-            get_round_score(&me, &opponent)
-        })
-        .sum()
+fn score_outcomes<R: BufRead>(rules: &Rules, input: R) -> Result<u64, String> {
+    total_score(rules, play_outcomes(rules, input))
 }
 
-fn solve_part_two<R: BufRead>(input: R) -> u32 {
-    input
-        .lines()
-        .map(|line| {
-            let (opponent_shape, outcome) = parse_line_shape_and_outcome(&line.unwrap());
-            let my_shape = get_shape_required_for_outcome(&opponent_shape, &outcome);
-            get_round_score(&my_shape, &opponent_shape)
-        })
-        .sum()
+fn total_score(
+    rules: &Rules,
+    mut rounds: impl Iterator<Item = Result<(Shape, Shape), String>>,
+) -> Result<u64, String> {
+    rounds.try_fold(0_u64, |total, round| {
+        let (opponent, me) = round?;
+        total
+            .checked_add(get_round_score(rules, &me, &opponent))
+            .ok_or_else(|| "The total score doesn't fit in 64 bits".to_string())
+    })
 }
 
-fn parse_line_shapes(line: &str) -> (Shape, Shape) {
-    let mut chars = line.chars();
+/// Scores the guide through a table of each line's score under the puzzle's rules, the table
+/// itself scored by the given function. A guide the table can't score is scored by the function
+/// too, to find the line that isn't a round.
+fn score_with_table(
+    input: &[u8],
+    score: fn(&Rules, &[u8]) -> Result<u64, String>,
+) -> Result<u64, String> {
+    let rules = Rules::default();
+    let table = ScoreTable::new(|line| {
+        score(&rules, line.as_bytes()).expect("Every line of the table is a round")
+    });
 
-    let opponent = chars.next().unwrap();
-    chars.next().unwrap();
-    let me = chars.next().unwrap();
+    match table.score(input) {
+        Some(total) => Ok(total),
+        None => score(&rules, input),
+    }
+}
 
-    (parse_shape(opponent), parse_shape(me))
+/// The shapes played in each round, as (opponent's shape, my shape), reading the second column
+/// as my shape.
+fn play_shapes<'a, R: BufRead + 'a>(
+    rules: &'a Rules,
+    input: R,
+) -> impl Iterator<Item = Result<(Shape, Shape), String>> + 'a {
+    parse_lines(input, move |line| parse_line_shapes(rules, line))
 }
 
-fn parse_line_shape_and_outcome(line: &str) -> (Shape, Outcome) {
-    let mut chars = line.chars();
+/// The shapes played in each round, as (opponent's shape, my shape), reading the second column
+/// as the outcome to play for.
+fn play_outcomes<'a, R: BufRead + 'a>(
+    rules: &'a Rules,
+    input: R,
+) -> impl Iterator<Item = Result<(Shape, Shape), String>> + 'a {
+    parse_lines(input, move |line| {
+        let (opponent_shape, outcome) = parse_line_shape_and_outcome(rules, line)?;
+        let my_shape = get_shape_required_for_outcome(&rules.game, &opponent_shape, &outcome);
+        Ok((opponent_shape, my_shape))
+    })
+}
+
+/// Parses each line of the input, numbering the lines in the errors.
+fn parse_lines<'a, R: BufRead + 'a, T>(
+    input: R,
+    parse: impl Fn(&str) -> Result<T, String> + 'a,
+) -> impl Iterator<Item = Result<T, String>> + 'a {
+    input.lines().enumerate().map(move |(i, line)| {
+        line.map_err(|error| error.to_string())
+            .and_then(|line| parse(&line))
+            .map_err(|error| format!("Line {}: {}", i + 1, error))
+    })
+}
 
-    let opponent_shape = chars.next().unwrap();
-    chars.next().unwrap();
-    let outcome = chars.next().unwrap();
+/// Scores the guide under other rules, e.g. `day2 score rpsls` or `day2 score rules.toml`.
+fn print_scores(input: &[u8], args: &[String]) -> Result<(), String> {
+    let rules = match args {
+        [rules] => load_rules(rules)?,
+        _ => return Err("Expected the rules to play by".to_string()),
+    };
+    let game = &rules.game;
 
-    (parse_shape(opponent_shape), parse_outcome(outcome))
+    let names = game
+        .shapes()
+        .map(|shape| game.name(&shape))
+        .collect::<Vec<_>>();
+    println!("Playing {}", names.join(", "));
+    println!(
+        "Reading the second column as shapes: {}",
+        score_shapes(&rules, input)?
+    );
+    println!(
+        "Reading the second column as outcomes: {}",
+        score_outcomes(&rules, input)?
+    );
+
+    Ok(())
 }
 
-fn parse_shape(input: char) -> Shape {
-    match input {
-        'A' => Shape::Rock,
-        'B' => Shape::Paper,
-        'C' => Shape::Scissors,
-        'X' => Shape::Rock,
-        'Y' => Shape::Paper,
-        'Z' => Shape::Scissors,
-        _ => panic!("Unknown input"),
+/// Scores the guide under every reading of the second column, by the puzzle's rules unless
+/// others are given.
+fn print_interpretations(input: &[u8], args: &[String]) -> Result<(), String> {
+    let rules = match args {
+        [] => Rules::default(),
+        [rules] => load_rules(rules)?,
+        _ => return Err("Expected at most the rules to play by".to_string()),
+    };
+    let input = std::str::from_utf8(input).map_err(|error| error.to_string())?;
+    let interpretations = interpretations::interpret(&rules, input)?;
+
+    for interpretation in &interpretations {
+        let reading = match interpretation.mapping {
+            Mapping::Shapes(_) => "shapes",
+            Mapping::Outcomes(_) => "outcomes",
+        };
+
+        println!(
+            "{:>8}  as {:<8}  {}",
+            interpretation.score,
+            reading,
+            interpretation.mapping.describe(&rules.game)
+        );
     }
-}
 
-fn parse_outcome(input: char) -> Outcome {
-    match input {
-        'X' => Outcome::Loss,
-        'Y' => Outcome::Draw,
-        'Z' => Outcome::Win,
-        _ => panic!("Unknown input"),
+    if let (Some(best), Some(worst)) = (interpretations.first(), interpretations.last()) {
+        println!("Best: {}", best.mapping.describe(&rules.game));
+        println!("Worst: {}", worst.mapping.describe(&rules.game));
     }
+
+    Ok(())
 }
 
-fn get_shape_required_for_outcome(opponent_shape: &Shape, outcome: &Outcome) -> Shape {
-    if *outcome == Outcome::Draw {
-        *opponent_shape
-    } else {
-        let losing_shape = get_losing_shape(opponent_shape);
+/// Lists how each round played out and scored, e.g. `day2 report outcomes csv rpsls`.
+fn print_report(input: &[u8], args: &[String]) -> Result<(), String> {
+    let (reading, format, rules) = match args {
+        [reading] => (reading, "table", Rules::default()),
+        [reading, format] => (reading, format.as_str(), Rules::default()),
+        [reading, format, rules] => (reading, format.as_str(), load_rules(rules)?),
+        _ => return Err("Expected how to read the second column".to_string()),
+    };
 
-        if *outcome == Outcome::Loss {
-            losing_shape
-        } else {
-            get_losing_shape(&losing_shape)
-        }
+    let report = match reading.as_str() {
+        "shapes" => Report::new(&rules, play_shapes(&rules, input))?,
+        "outcomes" => Report::new(&rules, play_outcomes(&rules, input))?,
+        _ => return Err(format!("Unknown reading '{}'", reading)),
+    };
+
+    match format {
+        "table" => print!("{}", report.to_table(&rules.game)),
+        "csv" => print!("{}", report.to_csv(&rules.game)),
+        _ => return Err(format!("Unknown format '{}'", format)),
     }
+
+    Ok(())
 }
 
-fn get_round_score(my_shape: &Shape, others_shape: &Shape) -> u32 {
-    let outcome = get_outcome(my_shape, others_shape);
+/// Plays the built-in strategies against each other and the guide's shapes, e.g.
+/// `day2 tournament 1000 7`. The seed is the random strategy's.
+fn print_tournament(input: &[u8], args: &[String]) -> Result<(), String> {
+    let parse = |arg: &String| {
+        arg.parse::<u64>()
+            .map_err(|_| format!("Expected a number, got '{}'", arg))
+    };
+    let (rounds, seed) = match args {
+        [] => (1000, 0),
+        [rounds] => (parse(rounds)?, 0),
+        [rounds, seed] => (parse(rounds)?, parse(seed)?),
+        _ => return Err("Expected at most the number of rounds and a seed".to_string()),
+    };
+
+    if rounds > MAX_TOURNAMENT_ROUNDS {
+        return Err(format!(
+            "Matches can be at most {} rounds long",
+            MAX_TOURNAMENT_ROUNDS
+        ));
+    }
+
+    let rules = Rules::default();
+    let guide = play_shapes(&rules, input)
+        .map(|round| round.map(|(_, me)| me))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if guide.is_empty() {
+        return Err("The guide has no rounds to follow".to_string());
+    }
+
+    let mut entrants = rules
+        .game
+        .shapes()
+        .map(|shape| {
+            let name = format!("Always {}", rules.game.name(&shape));
+            Entrant::new(&name, move || Constant(shape))
+        })
+        .collect::<Vec<_>>();
+    entrants.push(Entrant::new("Random", move || Random::new(seed)));
+    entrants.push(Entrant::new("Cycle", || Cycle));
+    entrants.push(Entrant::new("Frequency counter", FrequencyCounter::default));
+    entrants.push(Entrant::new("Markov (order 2)", || Markov::new(2)));
+    entrants.push(Entrant::new("Guide", move || {
+        FollowGuide::new(guide.clone())
+    }));
+
+    let standings = tournament::round_robin(&rules, &entrants, rounds as usize);
+
+    println!(
+        "{:>4}  {:<18}  {:>10}  {:>4}  {:>5}  {:>6}",
+        "rank", "strategy", "points", "wins", "draws", "losses"
+    );
 
-    get_shape_score(my_shape) + get_outcome_score(&outcome)
+    for (i, standing) in standings.iter().enumerate() {
+        println!(
+            "{:>4}  {:<18}  {:>10}  {:>4}  {:>5}  {:>6}",
+            i + 1,
+            standing.name,
+            standing.points,
+            standing.wins,
+            standing.draws,
+            standing.losses
+        );
+    }
+
+    Ok(())
 }
 
-fn get_shape_score(shape: &Shape) -> u32 {
-    match shape {
-        Shape::Rock => 1,
-        Shape::Paper => 2,
-        Shape::Scissors => 3,
+/// Predicts the opponent's shapes from the ones they played before, with each order of Markov
+/// predictor up to the given one (2 by default), and compares the scores with the guide's.
+fn print_predictions(input: &[u8], args: &[String]) -> Result<(), String> {
+    let max_order = match args {
+        [] => 2,
+        [order] => order
+            .parse::<usize>()
+            .map_err(|_| format!("Expected an order, got '{}'", order))?,
+        _ => return Err("Expected at most the highest order to predict with".to_string()),
+    };
+
+    let rules = Rules::default();
+    let opponent = play_shapes(&rules, input)
+        .map(|round| round.map(|(opponent, _)| opponent))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    println!(
+        "Following the guide: {} reading it as shapes, {} as outcomes",
+        score_shapes(&rules, input)?,
+        score_outcomes(&rules, input)?
+    );
+
+    for order in 0..=max_order {
+        let evaluation = predictor::evaluate(&rules, &opponent, order);
+        let accuracy = match evaluation.predicted {
+            0 => 0.0,
+            predicted => 100.0 * evaluation.correct as f64 / predicted as f64,
+        };
+
+        println!(
+            "Order {}: {} points, {} of {} shapes predicted ({:.1}%)",
+            order, evaluation.score, evaluation.correct, evaluation.predicted, accuracy
+        );
     }
+
+    Ok(())
+}
+
+/// Referees a match between two players connecting to the address, e.g.
+/// `day2 serve 127.0.0.1:7878 1000 match.txt`, and logs it as a strategy guide. No guide is
+/// read.
+fn run_server(args: &[String]) -> Result<(), String> {
+    let (address, rounds, log_path, rules) = match args {
+        [address, rounds, log_path] => (address, rounds, log_path, Rules::default()),
+        [address, rounds, log_path, rules] => (address, rounds, log_path, load_rules(rules)?),
+        _ => return Err("Expected an address, a number of rounds and a log file".to_string()),
+    };
+    let rounds = rounds
+        .parse::<usize>()
+        .map_err(|_| format!("Expected a number of rounds, got '{}'", rounds))?;
+
+    let listener = TcpListener::bind(address)
+        .map_err(|error| format!("Could not listen on {}: {}", address, error))?;
+    let log = File::create(log_path)
+        .map_err(|error| format!("Could not create {}: {}", log_path, error))?;
+
+    println!("Waiting for two players on {}", address);
+    let mut log = BufWriter::new(log);
+    let (first, second) =
+        network::serve(&listener, &rules, rounds, &mut log).map_err(|error| error.to_string())?;
+    log.flush()
+        .map_err(|error| format!("Could not write {}: {}", log_path, error))?;
+    println!("First player: {}, second player: {}", first, second);
+
+    Ok(())
 }
 
-fn get_outcome_score(outcome: &Outcome) -> u32 {
-    match outcome {
-        Outcome::Loss => 0,
-        Outcome::Draw => 3,
-        Outcome::Win => 6,
+/// Plays a match on a server with one of the built-in strategies, e.g.
+/// `day2 play 127.0.0.1:7878 markov`. The guide strategy follows the input's shapes.
+fn run_player(input: &[u8], args: &[String]) -> Result<(), String> {
+    let (address, strategy, rules) = match args {
+        [address, strategy] => (address, strategy, Rules::default()),
+        [address, strategy, rules] => (address, strategy, load_rules(rules)?),
+        _ => return Err("Expected an address and a strategy".to_string()),
+    };
+
+    let mut strategy: Box<dyn Strategy> = match strategy.as_str() {
+        "random" => Box::new(Random::new(0)),
+        "cycle" => Box::new(Cycle),
+        "frequency" => Box::new(FrequencyCounter::default()),
+        "markov" => Box::new(Markov::new(2)),
+        "guide" => {
+            let guide = play_shapes(&rules, input)
+                .map(|round| round.map(|(_, me)| me))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if guide.is_empty() {
+                return Err("The guide has no rounds to follow".to_string());
+            }
+
+            Box::new(FollowGuide::new(guide))
+        }
+        name => match rules.game.shape_named(name) {
+            Some(shape) => Box::new(Constant(shape)),
+            None => {
+                return Err(format!(
+                    "Unknown strategy '{}', expected random, cycle, frequency, markov, guide or \
+                     a shape to always play",
+                    name
+                ))
+            }
+        },
+    };
+
+    let (points, their_points) =
+        network::play(address, &rules, strategy.as_mut()).map_err(|error| error.to_string())?;
+    println!("Scored {} to the opponent's {}", points, their_points);
+
+    Ok(())
+}
+
+/// Prints a guide of the given number of rounds with the target score, e.g.
+/// `day2 reverse outcomes 2500 12345 > fixture.txt`. No guide is read.
+fn print_reverse_guide(args: &[String]) -> Result<(), String> {
+    let (reading, rounds, target, rules) = match args {
+        [reading, rounds, target] => (reading, rounds, target, Rules::default()),
+        [reading, rounds, target, rules] => (reading, rounds, target, load_rules(rules)?),
+        _ => return Err("Expected a reading, a number of rounds and a score".to_string()),
+    };
+
+    let reading = match reading.as_str() {
+        "shapes" => Reading::Shapes,
+        "outcomes" => Reading::Outcomes,
+        _ => return Err(format!("Unknown reading '{}'", reading)),
+    };
+    let rounds = rounds
+        .parse::<usize>()
+        .map_err(|_| format!("Expected a number of rounds, got '{}'", rounds))?;
+
+    if rounds > MAX_GUIDE_ROUNDS {
+        return Err(format!(
+            "Guides can be at most {} rounds long",
+            MAX_GUIDE_ROUNDS
+        ));
+    }
+
+    let target = target
+        .parse::<u64>()
+        .map_err(|_| format!("Expected a score, got '{}'", target))?;
+
+    let guide =
+        reverse::build_guide(&rules, reading, rounds, target).map_err(|error| error.to_string())?;
+
+    for (opponent, second) in guide {
+        println!("{} {}", opponent, second);
     }
+
+    Ok(())
 }
 
-fn get_outcome(my_shape: &Shape, others_shape: &Shape) -> Outcome {
-    if *my_shape == *others_shape {
-        Outcome::Draw
-    } else {
-        let losing_shape = get_losing_shape(my_shape);
+/// Finds the best mix of shapes to play at random under the rules, e.g.
+/// `day2 equilibrium rules.toml`. No guide is read.
+fn print_equilibrium(args: &[String]) -> Result<(), String> {
+    let rules = match args {
+        [] => Rules::default(),
+        [rules] => load_rules(rules)?,
+        _ => return Err("Expected at most the rules to play by".to_string()),
+    };
+    let equilibrium = equilibrium::solve(&rules);
+
+    println!("{:<10}  {:>6}  {:>8}", "shape", "mine", "theirs");
+
+    for shape in rules.game.shapes() {
+        println!(
+            "{:<10}  {:>5.1}%  {:>7.1}%",
+            rules.game.name(&shape),
+            100.0 * equilibrium.mine[shape.0],
+            100.0 * equilibrium.theirs[shape.0]
+        );
+    }
 
-        if *others_shape == losing_shape {
-            Outcome::Win
-        } else {
-            Outcome::Loss
+    println!("Value: {:.3} points a round", equilibrium.value);
+
+    Ok(())
+}
+
+/// Loads the rules named by an argument: one of the built-in games, or a rules file.
+fn load_rules(name: &str) -> Result<Rules, String> {
+    match name {
+        "rps" => Ok(Rules::default()),
+        "rpsls" => Rules::for_game(Game::rock_paper_scissors_lizard_spock())
+            .map_err(|error| error.to_string()),
+        path => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|error| format!("Could not read {}: {}", path, error))?;
+            Rules::from_toml(&text).map_err(|error| format!("{}: {}", path, error))
         }
     }
 }
 
-fn get_losing_shape(shape: &Shape) -> Shape {
-    match shape {
-        Shape::Rock => Shape::Scissors,
-        Shape::Paper => Shape::Rock,
-        Shape::Scissors => Shape::Paper,
+fn parse_line_shapes(rules: &Rules, line: &str) -> Result<(Shape, Shape), String> {
+    let (opponent, me) = split_line(line)?;
+
+    Ok((
+        parse_opponent_shape(rules, opponent)?,
+        parse_my_shape(rules, me)?,
+    ))
+}
+
+fn parse_line_shape_and_outcome(rules: &Rules, line: &str) -> Result<(Shape, Outcome), String> {
+    let (opponent_shape, outcome) = split_line(line)?;
+
+    Ok((
+        parse_opponent_shape(rules, opponent_shape)?,
+        parse_outcome(rules, outcome)?,
+    ))
+}
+
+/// The two symbols of a line, separated by a space.
+fn split_line(line: &str) -> Result<(char, char), String> {
+    let mut chars = line.chars();
+
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some(first), Some(' '), Some(second), None) => Ok((first, second)),
+        _ => Err(format!(
+            "Expected two symbols separated by a space, got '{}'",
+            line
+        )),
     }
 }
 
-#[derive(PartialEq, Clone, Copy)]
-enum Shape {
-    Rock,
-    Paper,
-    Scissors,
+fn parse_opponent_shape(rules: &Rules, input: char) -> Result<Shape, String> {
+    rules
+        .opponent_shape(input)
+        .ok_or_else(|| format!("Unknown opponent's symbol '{}'", input))
 }
 
-#[derive(PartialEq)]
-enum Outcome {
-    Win,
-    Draw,
-    Loss,
+fn parse_my_shape(rules: &Rules, input: char) -> Result<Shape, String> {
+    rules
+        .my_shape(input)
+        .ok_or_else(|| format!("Unknown symbol of my shape '{}'", input))
+}
+
+fn parse_outcome(rules: &Rules, input: char) -> Result<Outcome, String> {
+    rules
+        .outcome(input)
+        .ok_or_else(|| format!("Unknown symbol of an outcome '{}'", input))
+}
+
+fn generate_input(rounds: usize) -> String {
+    let mut rng = Rng::new(2);
+
+    (0..rounds)
+        .map(|_| {
+            let opponent = (b'A' + rng.below(3) as u8) as char;
+            let me = (b'X' + rng.below(3) as u8) as char;
+            format!("{} {}\n", opponent, me)
+        })
+        .collect()
+}
+
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    lines.iter().filter_map(check_round).collect()
+}
+
+fn check_round(line: &Line) -> Option<Violation> {
+    let bytes = line.text.as_bytes();
+
+    if bytes.is_empty() {
+        Some(Violation::new(
+            line,
+            1,
+            "Blank line instead of a round",
+            "remove the blank line",
+        ))
+    } else if !b"ABC".contains(&bytes[0]) {
+        Some(Violation::new(
+            line,
+            1,
+            "Expected the opponent's shape",
+            "use A, B or C",
+        ))
+    } else if bytes.get(1) != Some(&b' ') {
+        Some(Violation::new(
+            line,
+            2,
+            "Expected a space between the columns",
+            "separate the columns with a single space",
+        ))
+    } else if !bytes.get(2).is_some_and(|b| b"XYZ".contains(b)) {
+        Some(Violation::new(
+            line,
+            3,
+            "Expected the second column",
+            "use X, Y or Z",
+        ))
+    } else if bytes.len() > 3 {
+        check_trailing_whitespace(line).or_else(|| {
+            Some(Violation::new(
+                line,
+                4,
+                "Unexpected text after the round",
+                "remove everything after the second column",
+            ))
+        })
+    } else {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -165,12 +633,62 @@ mod tests {
     #[test]
     fn test_part_one() {
         let input = open_example();
-        assert_eq!(solve_part_one(input), 15);
+        assert_eq!(solve_part_one(input), Ok(15));
     }
 
     #[test]
     fn test_part_two() {
         let input = open_example();
-        assert_eq!(solve_part_two(input), 12);
+        assert_eq!(solve_part_two(input), Ok(12));
+    }
+
+    #[test]
+    fn test_score_errors() {
+        let rules = Rules::from_toml("[symbols]\nme = 'rps'").unwrap();
+
+        assert_eq!(score_shapes(&rules, &b"A r\nB p\n"[..]), Ok(4 + 5));
+        assert_eq!(
+            score_shapes(&rules, &b"A r\nB Y\n"[..]),
+            Err("Line 2: Unknown symbol of my shape 'Y'".to_string())
+        );
+        assert_eq!(
+            score_outcomes(&rules, &b"A X\nBZ\n"[..]),
+            Err("Line 2: Expected two symbols separated by a space, got 'BZ'".to_string())
+        );
+        assert!(score_shapes(&Rules::default(), &b"A Y\n\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_score_with_table() {
+        let score = |rules: &Rules, guide: &[u8]| score_shapes(rules, guide);
+
+        assert_eq!(score_with_table(b"A Y\nB X\nC Z\n", score), Ok(15));
+        assert_eq!(
+            score_with_table(b"A Y\nB X\nC Z\n\n", score),
+            Err("Line 4: Expected two symbols separated by a space, got ''".to_string())
+        );
+    }
+
+    #[test]
+    fn test_large_scores() {
+        let rules = Rules::from_toml("shape_scores = [1, 2, 3000000000]").unwrap();
+
+        assert_eq!(
+            score_shapes(&rules, &b"C Z\nC Z\n"[..]),
+            Ok(2 * (3_000_000_000 + 3))
+        );
+    }
+
+    #[test]
+    fn test_check_input() {
+        let example = std::fs::read("example.txt").unwrap();
+        assert!(aoc::check::check(&example, Some(check_input)).is_empty());
+
+        let violations = aoc::check::check(b"A Y \nD X\nA  Z\n", Some(check_input));
+        let positions = violations
+            .iter()
+            .map(|violation| (violation.line, violation.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(1, 4), (2, 1), (3, 3)]);
     }
 }
@@ -0,0 +1,276 @@
+//! Matches between two programs over TCP, with a line protocol. The server sends each player
+//! `MATCH <rounds>`, then for each round `MOVE`, to which the player answers with the name of a
+//! shape, e.g. `Rock`, and `RESULT <their shape> <points>` once both have moved. After the last
+//! round it sends `END <points> <their points>`. A player breaking the protocol is sent
+//! `ERROR <message>` and the match is called off.
+
+use std::{
+    fmt::{self, Display},
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+use crate::{
+    game::Shape,
+    rules::{get_round_score, Rules},
+    strategy::Strategy,
+};
+
+/// How long to wait for a player's move before calling the match off.
+const MOVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum MatchError {
+    Io(io::Error),
+    /// A message that isn't part of the protocol, or a move that isn't a shape.
+    Protocol(String),
+}
+
+impl Display for MatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchError::Io(error) => write!(f, "{}", error),
+            MatchError::Protocol(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<io::Error> for MatchError {
+    fn from(error: io::Error) -> Self {
+        MatchError::Io(error)
+    }
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_read_timeout(Some(MOVE_TIMEOUT))?;
+
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    fn send(&mut self, message: &str) -> io::Result<()> {
+        writeln!(self.writer, "{}", message)
+    }
+
+    fn receive(&mut self) -> Result<String, MatchError> {
+        let mut line = String::new();
+
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(MatchError::Protocol(
+                "The connection was closed".to_string(),
+            ));
+        }
+
+        Ok(line.trim_end().to_string())
+    }
+}
+
+/// Waits for two players to connect and plays a match of the given number of rounds between
+/// them. Each round is logged as a line of a strategy guide, the first player's shape as the
+/// opponent's and the second's as mine. Returns the points each player scored.
+pub fn serve<W: Write>(
+    listener: &TcpListener,
+    rules: &Rules,
+    rounds: usize,
+    log: &mut W,
+) -> Result<(u64, u64), MatchError> {
+    let mut players = [
+        Connection::new(listener.accept()?.0)?,
+        Connection::new(listener.accept()?.0)?,
+    ];
+    let mut points = [0, 0];
+
+    for player in &mut players {
+        player.send(&format!("MATCH {}", rounds))?;
+    }
+
+    for _ in 0..rounds {
+        let mut shapes = [Shape(0); 2];
+
+        for (player, shape) in players.iter_mut().zip(&mut shapes) {
+            player.send("MOVE")?;
+            let name = player.receive()?;
+
+            *shape = match rules.game.shape_named(&name) {
+                Some(shape) => shape,
+                None => {
+                    let message = format!("Unknown shape '{}'", name);
+                    player.send(&format!("ERROR {}", message))?;
+                    return Err(MatchError::Protocol(message));
+                }
+            };
+        }
+
+        for (i, player) in players.iter_mut().enumerate() {
+            let (mine, theirs) = (shapes[i], shapes[1 - i]);
+            let score = get_round_score(rules, &mine, &theirs);
+            points[i] += score;
+
+            player.send(&format!("RESULT {} {}", rules.game.name(&theirs), score))?;
+        }
+
+        writeln!(
+            log,
+            "{} {}",
+            rules.opponent_symbols()[shapes[0].0],
+            rules.my_symbols()[shapes[1].0]
+        )?;
+    }
+
+    for (i, player) in players.iter_mut().enumerate() {
+        player.send(&format!("END {} {}", points[i], points[1 - i]))?;
+    }
+
+    Ok((points[0], points[1]))
+}
+
+/// Connects to a server and plays a match with the strategy. Returns the points scored by the
+/// strategy and by its opponent.
+pub fn play(
+    address: &str,
+    rules: &Rules,
+    strategy: &mut dyn Strategy,
+) -> Result<(u64, u64), MatchError> {
+    play_over(TcpStream::connect(address)?, rules, strategy)
+}
+
+fn play_over(
+    stream: TcpStream,
+    rules: &Rules,
+    strategy: &mut dyn Strategy,
+) -> Result<(u64, u64), MatchError> {
+    let mut server = Connection::new(stream)?;
+    let mut history = Vec::new();
+    let mut mine = None;
+
+    loop {
+        let message = server.receive()?;
+        let unexpected = || MatchError::Protocol(format!("Unexpected message '{}'", message));
+
+        // Shape names may have spaces, so a result's points are split off its end
+        match message.split_once(' ').unwrap_or((&message, "")) {
+            ("MATCH", _) => {}
+            ("MOVE", "") => {
+                let shape = strategy.choose(&rules.game, &history);
+                mine = Some(shape);
+                server.send(rules.game.name(&shape))?;
+            }
+            ("RESULT", result) => {
+                let (theirs, _) = result.rsplit_once(' ').ok_or_else(unexpected)?;
+                let theirs = rules.game.shape_named(theirs).ok_or_else(|| {
+                    MatchError::Protocol(format!("Unknown shape '{}' from the server", theirs))
+                })?;
+                let mine = mine
+                    .take()
+                    .ok_or_else(|| MatchError::Protocol("A result before a move".to_string()))?;
+
+                history.push((mine, theirs));
+            }
+            ("END", all_points) => {
+                let (points, their_points) = all_points.split_once(' ').ok_or_else(unexpected)?;
+                let parse = |points: &str| {
+                    points.parse::<u64>().map_err(|_| {
+                        MatchError::Protocol(format!("Expected points, got '{}'", points))
+                    })
+                };
+
+                return Ok((parse(points)?, parse(their_points)?));
+            }
+            ("ERROR", reason) => return Err(MatchError::Protocol(reason.to_string())),
+            _ => return Err(unexpected()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::strategy::{Constant, Cycle};
+
+    use super::*;
+
+    #[test]
+    fn test_match() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        // The first to connect is the first player, and connections are accepted in order
+        let stream = TcpStream::connect(&address).unwrap();
+        let first =
+            thread::spawn(move || play_over(stream, &Rules::default(), &mut Cycle).unwrap());
+
+        let mut log = Vec::new();
+        let server = thread::spawn(move || {
+            let points = serve(&listener, &Rules::default(), 4, &mut log).unwrap();
+            (points, log)
+        });
+
+        let second = play(&address, &Rules::default(), &mut Constant(Shape(1))).unwrap();
+        let (points, log) = server.join().unwrap();
+
+        // Rock, Paper, Scissors and Rock against Paper
+        assert_eq!(points, (1 + 5 + 9 + 1, 8 + 5 + 2 + 8));
+        assert_eq!(first.join().unwrap(), points);
+        assert_eq!(second, (points.1, points.0));
+        assert_eq!(String::from_utf8(log).unwrap(), "A Y\nB Y\nC Y\nA Y\n");
+    }
+
+    #[test]
+    fn test_shape_names_with_spaces() {
+        let rules = Rules::from_toml("shapes = ['Big Rock', 'Paper', 'Sharp Scissors']").unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let stream = TcpStream::connect(&address).unwrap();
+        let first = {
+            let rules = rules.clone();
+            thread::spawn(move || play_over(stream, &rules, &mut Constant(Shape(2))).unwrap())
+        };
+        let server = {
+            let rules = rules.clone();
+            thread::spawn(move || serve(&listener, &rules, 2, &mut io::sink()).unwrap())
+        };
+
+        let second = play(&address, &rules, &mut Constant(Shape(0))).unwrap();
+
+        // Big Rock beats Sharp Scissors
+        assert_eq!(second, (7 + 7, 3 + 3));
+        assert_eq!(first.join().unwrap(), (3 + 3, 7 + 7));
+        assert_eq!(server.join().unwrap(), (3 + 3, 7 + 7));
+    }
+
+    #[test]
+    fn test_unknown_shape() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || serve(&listener, &Rules::default(), 1, &mut io::sink()));
+
+        let mut first = TcpStream::connect(address).unwrap();
+        let _second = TcpStream::connect(address).unwrap();
+        writeln!(first, "Dynamite").unwrap();
+
+        assert!(matches!(
+            server.join().unwrap(),
+            Err(MatchError::Protocol(message)) if message == "Unknown shape 'Dynamite'"
+        ));
+
+        let mut reply = String::new();
+        let mut reader = BufReader::new(&first);
+        while !reply.starts_with("ERROR") {
+            reply.clear();
+            reader.read_line(&mut reply).unwrap();
+        }
+        assert_eq!(reply, "ERROR Unknown shape 'Dynamite'\n");
+    }
+}
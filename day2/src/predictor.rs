@@ -0,0 +1,182 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::{
+    game::{get_shape_required_for_outcome, Game, Outcome, Shape},
+    rules::{get_round_score, Rules},
+    strategy::Strategy,
+};
+
+/// Predicts the opponent's next shape from what followed their last `order` shapes before,
+/// falling back to shorter histories it hasn't seen yet, and plays what beats it.
+pub struct Markov {
+    order: usize,
+    played: Vec<Shape>,
+    /// How often each shape followed each history of up to `order` shapes.
+    followers: HashMap<Vec<Shape>, Vec<u32>>,
+}
+
+impl Markov {
+    pub fn new(order: usize) -> Self {
+        Self {
+            order,
+            played: Vec::new(),
+            followers: HashMap::new(),
+        }
+    }
+
+    /// Records the opponent's shape for the round.
+    pub fn observe(&mut self, game: &Game, shape: Shape) {
+        let length = self.played.len();
+
+        for order in 0..=self.order.min(length) {
+            let counts = self
+                .followers
+                .entry(self.played[length - order..].to_vec())
+                .or_insert_with(|| vec![0; game.shape_count()]);
+            counts[shape.0] += 1;
+        }
+
+        self.played.push(shape);
+    }
+
+    /// The shape the opponent most often played after the longest recent history seen before,
+    /// the first of them in the game's order on a tie. None before any shape is observed.
+    pub fn predict(&self) -> Option<Shape> {
+        let length = self.played.len();
+
+        (0..=self.order.min(length)).rev().find_map(|order| {
+            let counts = self.followers.get(&self.played[length - order..])?;
+            let (shape, _) = counts
+                .iter()
+                .enumerate()
+                .max_by_key(|(shape, count)| (**count, Reverse(*shape)))?;
+
+            Some(Shape(shape))
+        })
+    }
+}
+
+impl Strategy for Markov {
+    fn choose(&mut self, game: &Game, history: &[(Shape, Shape)]) -> Shape {
+        // Only the rounds played since the last choice are observed, unless a new match started
+        if self.played.len() > history.len() {
+            *self = Markov::new(self.order);
+        }
+
+        for (_, theirs) in &history[self.played.len()..] {
+            self.observe(game, *theirs);
+        }
+
+        respond(game, self.predict())
+    }
+}
+
+fn respond(game: &Game, prediction: Option<Shape>) -> Shape {
+    match prediction {
+        Some(shape) => get_shape_required_for_outcome(game, &shape, &Outcome::Win),
+        None => Shape(0),
+    }
+}
+
+/// How a predictor did against a sequence of the opponent's shapes.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Evaluation {
+    pub score: u64,
+    /// Rounds in which the opponent's shape was predicted, and in which the prediction was right.
+    pub predicted: usize,
+    pub correct: usize,
+}
+
+/// Plays a Markov predictor of the given order against the opponent's shapes.
+pub fn evaluate(rules: &Rules, opponent: &[Shape], order: usize) -> Evaluation {
+    let mut markov = Markov::new(order);
+    let mut evaluation = Evaluation {
+        score: 0,
+        predicted: 0,
+        correct: 0,
+    };
+
+    for shape in opponent {
+        let prediction = markov.predict();
+
+        if let Some(predicted) = prediction {
+            evaluation.predicted += 1;
+            evaluation.correct += (predicted == *shape) as usize;
+        }
+
+        evaluation.score += get_round_score(rules, &respond(&rules.game, prediction), shape);
+        markov.observe(&rules.game, *shape);
+    }
+
+    evaluation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_example() {
+        let rules = Rules::default();
+        let opponent = [Shape(0), Shape(1), Shape(2)];
+
+        assert_eq!(
+            evaluate(&rules, &opponent, 1),
+            Evaluation {
+                // Rock against Rock with nothing to go on, then Rock predicted twice
+                score: 4 + 5 + 2,
+                predicted: 2,
+                correct: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_learns_cycle() {
+        let rules = Rules::default();
+        let opponent = (0..90).map(|i| Shape(i % 3)).collect::<Vec<_>>();
+        let evaluation = evaluate(&rules, &opponent, 1);
+
+        // Only guesses from the overall counts until each shape has been followed once
+        assert_eq!(evaluation.predicted, 89);
+        assert_eq!(evaluation.correct, 87);
+        assert_eq!(evaluation.score, 4 + 5 + 2 + 8 + 86 * 8);
+    }
+
+    #[test]
+    fn test_strategy_matches_evaluation() {
+        let rules = Rules::default();
+        let opponent = [0, 0, 1, 0, 0, 1, 2, 0, 0, 1].map(Shape);
+        let mut markov = Markov::new(2);
+        let mut history = Vec::new();
+        let mut score = 0;
+
+        for theirs in opponent {
+            let mine = markov.choose(&rules.game, &history);
+            score += get_round_score(&rules, &mine, &theirs);
+            history.push((mine, theirs));
+        }
+
+        assert_eq!(score, evaluate(&rules, &opponent, 2).score);
+    }
+
+    #[test]
+    fn test_strategy_starts_over() {
+        let game = Game::rock_paper_scissors();
+        let opponent = [0, 1, 2, 0, 1].map(Shape);
+        let mut markov = Markov::new(1);
+        let mut play = || {
+            let mut history = Vec::new();
+
+            for theirs in opponent {
+                let mine = markov.choose(&game, &history);
+                history.push((mine, theirs));
+            }
+
+            history
+        };
+
+        let first = play();
+        assert_eq!(play(), first);
+    }
+}
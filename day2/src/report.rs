@@ -0,0 +1,250 @@
+use std::{borrow::Cow, fmt::Write};
+
+use crate::{
+    game::{get_outcome, Game, Outcome, Shape},
+    rules::{get_outcome_score, get_round_score, get_shape_score, Rules},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: Shape,
+    pub me: Shape,
+    pub outcome: Outcome,
+    pub shape_score: u32,
+    pub outcome_score: u32,
+    pub score: u64,
+}
+
+/// How each round of a guide played out and scored.
+#[derive(Debug)]
+pub struct Report {
+    pub rounds: Vec<Round>,
+}
+
+impl Report {
+    /// Plays the rounds, given as (opponent's shape, my shape) or why a line isn't a round.
+    /// Fails on the first line that isn't, or if the total doesn't fit in 64 bits.
+    pub fn new<I: IntoIterator<Item = Result<(Shape, Shape), String>>>(
+        rules: &Rules,
+        rounds: I,
+    ) -> Result<Self, String> {
+        let rounds = rounds
+            .into_iter()
+            .map(|round| {
+                let (opponent, me) = round?;
+                let outcome = get_outcome(&rules.game, &me, &opponent);
+
+                Ok(Round {
+                    opponent,
+                    me,
+                    outcome,
+                    shape_score: get_shape_score(rules, &me),
+                    outcome_score: get_outcome_score(rules, &outcome),
+                    score: get_round_score(rules, &me, &opponent),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        rounds
+            .iter()
+            .try_fold(0_u64, |total, round| total.checked_add(round.score))
+            .ok_or_else(|| "The total score doesn't fit in 64 bits".to_string())?;
+
+        Ok(Self { rounds })
+    }
+
+    /// The total of every round, which `new` checked fits, as does any part of it.
+    pub fn total(&self) -> u64 {
+        self.rounds.iter().map(|round| round.score).sum()
+    }
+
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.rounds
+            .iter()
+            .filter(|round| round.outcome == outcome)
+            .count()
+    }
+
+    /// How many rounds I played each shape in, and the points they scored.
+    pub fn points_by_shape(&self, game: &Game) -> Vec<(Shape, usize, u64)> {
+        game.shapes()
+            .map(|shape| {
+                let rounds = self.rounds.iter().filter(|round| round.me == shape);
+                let points = rounds.clone().map(|round| round.score).sum();
+
+                (shape, rounds.count(), points)
+            })
+            .collect()
+    }
+
+    /// A table of the rounds, followed by the totals.
+    pub fn to_table(&self, game: &Game) -> String {
+        let mut table = format!(
+            "{:>6}  {:<10}  {:<10}  {:<7}  {:>5}  {:>7}  {:>5}\n",
+            "round", "opponent", "me", "outcome", "shape", "outcome", "score"
+        );
+
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(
+                table,
+                "{:>6}  {:<10}  {:<10}  {:<7}  {:>5}  {:>7}  {:>5}",
+                i + 1,
+                game.name(&round.opponent),
+                game.name(&round.me),
+                round.outcome.to_string(),
+                round.shape_score,
+                round.outcome_score,
+                round.score
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            table,
+            "\nWins: {}, draws: {}, losses: {}",
+            self.count(Outcome::Win),
+            self.count(Outcome::Draw),
+            self.count(Outcome::Loss)
+        )
+        .unwrap();
+
+        for (shape, rounds, points) in self.points_by_shape(game) {
+            writeln!(
+                table,
+                "{}: {} points in {} rounds",
+                game.name(&shape),
+                points,
+                rounds
+            )
+            .unwrap();
+        }
+
+        writeln!(table, "Total: {}", self.total()).unwrap();
+
+        table
+    }
+
+    /// A row for each round, without the totals, which a spreadsheet can add up.
+    pub fn to_csv(&self, game: &Game) -> String {
+        let mut csv = "round,opponent,me,outcome,shape_score,outcome_score,score\n".to_string();
+
+        for (i, round) in self.rounds.iter().enumerate() {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                i + 1,
+                csv_field(game.name(&round.opponent)),
+                csv_field(game.name(&round.me)),
+                round.outcome,
+                round.shape_score,
+                round.outcome_score,
+                round.score
+            )
+            .unwrap();
+        }
+
+        csv
+    }
+}
+
+/// Quotes a field holding a separator, quote or line break, doubling its quotes, as RFC 4180
+/// has it.
+fn csv_field(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_report(rules: &Rules) -> Report {
+        Report::new(
+            rules,
+            [
+                Ok((Shape(0), Shape(1))),
+                Ok((Shape(1), Shape(0))),
+                Ok((Shape(2), Shape(2))),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_report() {
+        let rules = Rules::default();
+        let report = example_report(&rules);
+
+        assert_eq!(report.total(), 15);
+        assert_eq!(
+            report.rounds[0],
+            Round {
+                opponent: Shape(0),
+                me: Shape(1),
+                outcome: Outcome::Win,
+                shape_score: 2,
+                outcome_score: 6,
+                score: 8,
+            }
+        );
+        assert_eq!(
+            [Outcome::Win, Outcome::Draw, Outcome::Loss].map(|outcome| report.count(outcome)),
+            [1, 1, 1]
+        );
+        assert_eq!(
+            report.points_by_shape(&rules.game),
+            vec![(Shape(0), 1, 1), (Shape(1), 1, 8), (Shape(2), 1, 6)]
+        );
+    }
+
+    #[test]
+    fn test_large_scores() {
+        let rules = Rules::from_toml("shape_scores = [1, 2, 3000000000]").unwrap();
+        let report =
+            Report::new(&rules, [Ok((Shape(2), Shape(2))), Ok((Shape(2), Shape(2)))]).unwrap();
+
+        assert_eq!(report.total(), 6_000_000_006);
+        assert_eq!(
+            report.points_by_shape(&rules.game)[2],
+            (Shape(2), 2, 6_000_000_006)
+        );
+    }
+
+    #[test]
+    fn test_unknown_symbols() {
+        let rules = Rules::default();
+
+        assert_eq!(
+            Report::new(
+                &rules,
+                [Ok((Shape(0), Shape(1))), Err("Line 2: x".to_string())]
+            )
+            .unwrap_err(),
+            "Line 2: x"
+        );
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let rules = Rules::default();
+        let csv = example_report(&rules).to_csv(&rules.game);
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1], "1,Rock,Paper,win,2,6,8");
+        assert_eq!(lines[2], "2,Paper,Rock,loss,1,0,1");
+    }
+
+    #[test]
+    fn test_to_csv_quotes_names() {
+        let rules = Rules::from_toml(r#"shapes = ['Rock, "big"', 'Paper', 'Scissors']"#).unwrap();
+        let csv = example_report(&rules).to_csv(&rules.game);
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[1], r#"1,"Rock, ""big""",Paper,win,2,6,8"#);
+        assert_eq!(lines[2], r#"2,Paper,"Rock, ""big""",loss,1,0,1"#);
+    }
+}
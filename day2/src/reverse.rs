@@ -0,0 +1,326 @@
+use std::fmt::{self, Display};
+
+use crate::{
+    game::get_shape_required_for_outcome,
+    rules::{get_round_score, Rules},
+};
+
+/// The most amounts the dynamic programming keeps the fewest lines of. The amounts left for it
+/// grow with the square of the largest gain, so rules with scores far apart need too many.
+const MAX_AMOUNTS: u64 = 1 << 20;
+
+/// How the second column of a guide is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    Shapes,
+    Outcomes,
+}
+
+/// Why no guide has the score asked for.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Impossible {
+    /// The score is below what the lowest scoring round in every round adds up to, or above
+    /// what the highest does.
+    OutOfRange { lowest: u64, highest: u64 },
+    /// The score is in range, but no combination of rounds adds up to it.
+    Unreachable,
+    /// Guides of that many rounds can score more than fits in 64 bits.
+    TooManyRounds,
+    /// The lines' scores are too far apart to search for the rounds adding up to the score.
+    TooFarApart,
+}
+
+impl Display for Impossible {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Impossible::OutOfRange { lowest, highest } => {
+                write!(
+                    f,
+                    "Guides of that many rounds score from {} to {}",
+                    lowest, highest
+                )
+            }
+            Impossible::Unreachable => write!(f, "No combination of rounds adds up to the score"),
+            Impossible::TooManyRounds => write!(f, "Guides of that many rounds score too much"),
+            Impossible::TooFarApart => write!(
+                f,
+                "The scores of the rounds are too far apart to search for a guide"
+            ),
+        }
+    }
+}
+
+/// Builds a guide of the given number of rounds that scores the target when read as the
+/// reading says, as its lines' (first column, second column) symbols.
+///
+/// Every round is first made the lowest scoring line, so that what's left of the target is made
+/// up of how much more the others score. The fewest lines adding up to that are found by dynamic
+/// programming, and the guide exists if they fit in the rounds.
+///
+/// Among any `g` lines gaining less than the largest gain `g`, some add up to a multiple of it, and
+/// fewer lines of the largest gain can take their place. So the fewest lines gain less than `g`
+/// in under `g` lines, and all but the last `g * g` of what's left are made up with the largest.
+/// Gains are counted in units of their greatest common divisor, so that rules scaling the
+/// puzzle's scores are searched as quickly.
+pub fn build_guide(
+    rules: &Rules,
+    reading: Reading,
+    rounds: usize,
+    target: u64,
+) -> Result<Vec<(char, char)>, Impossible> {
+    let second_symbols = match reading {
+        Reading::Shapes => rules.my_symbols(),
+        Reading::Outcomes => rules.outcome_symbols(),
+    };
+
+    let mut lines = Vec::new();
+
+    for opponent in rules.opponent_symbols() {
+        for second in second_symbols {
+            lines.push((
+                (*opponent, *second),
+                score_line(rules, reading, *opponent, *second),
+            ));
+        }
+    }
+
+    let (lowest_line, lowest) = *lines.iter().min_by_key(|(_, score)| *score).unwrap();
+    let highest = lines.iter().map(|(_, score)| *score).max().unwrap();
+
+    let total = |score: u64| {
+        (rounds as u64)
+            .checked_mul(score)
+            .ok_or(Impossible::TooManyRounds)
+    };
+    let (lowest_total, highest_total) = (total(lowest)?, total(highest)?);
+
+    if target < lowest_total || target > highest_total {
+        return Err(Impossible::OutOfRange {
+            lowest: lowest_total,
+            highest: highest_total,
+        });
+    }
+
+    let unit = lines
+        .iter()
+        .fold(0, |unit, (_, score)| gcd(unit, score - lowest));
+    let gain = |score: u64| (score - lowest) / unit.max(1);
+
+    let (largest_line, largest_gain) = lines
+        .iter()
+        .map(|(line, score)| (*line, gain(*score)))
+        .max_by_key(|(_, gain)| *gain)
+        .unwrap();
+
+    let mut extra = target - lowest_total;
+    let mut guide = Vec::new();
+
+    if unit > 0 {
+        if !extra.is_multiple_of(unit) {
+            return Err(Impossible::Unreachable);
+        }
+
+        extra /= unit;
+    }
+
+    if largest_gain > 0 {
+        let largest_count = extra
+            .saturating_sub(largest_gain.saturating_mul(largest_gain))
+            .div_ceil(largest_gain);
+
+        if largest_count > rounds as u64 {
+            return Err(Impossible::Unreachable);
+        }
+
+        guide.resize(largest_count as usize, largest_line);
+        extra -= largest_count * largest_gain;
+    }
+
+    if extra >= MAX_AMOUNTS {
+        return Err(Impossible::TooFarApart);
+    }
+
+    let extra = extra as usize;
+
+    // For each amount up to the extra, the fewest lines scoring that much more than the lowest,
+    // and the last of them
+    let mut fewest = vec![None::<(usize, usize)>; extra + 1];
+    fewest[0] = Some((0, usize::MAX));
+
+    for amount in 1..=extra {
+        fewest[amount] = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, score))| {
+                let gain = gain(*score) as usize;
+                let (count, _) = fewest[amount.checked_sub(gain)?]?;
+                (gain > 0).then_some((count + 1, i))
+            })
+            .min();
+    }
+
+    match fewest[extra] {
+        Some((count, _)) if guide.len() + count <= rounds => {}
+        _ => return Err(Impossible::Unreachable),
+    }
+
+    let mut amount = extra;
+
+    while amount > 0 {
+        let (_, i) = fewest[amount].unwrap();
+        let (line, score) = lines[i];
+
+        guide.push(line);
+        amount -= gain(score) as usize;
+    }
+
+    guide.resize(rounds, lowest_line);
+
+    Ok(guide)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn score_line(rules: &Rules, reading: Reading, opponent: char, second: char) -> u64 {
+    let opponent = rules.opponent_shape(opponent).unwrap();
+    let mine = match reading {
+        Reading::Shapes => rules.my_shape(second).unwrap(),
+        Reading::Outcomes => {
+            let outcome = rules.outcome(second).unwrap();
+            get_shape_required_for_outcome(&rules.game, &opponent, &outcome)
+        }
+    };
+
+    get_round_score(rules, &mine, &opponent)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{score_outcomes, score_shapes};
+
+    use super::*;
+
+    fn to_text(guide: &[(char, char)]) -> String {
+        guide
+            .iter()
+            .map(|(opponent, second)| format!("{} {}\n", opponent, second))
+            .collect()
+    }
+
+    #[test]
+    fn test_build_guide() {
+        let rules = Rules::default();
+
+        for (rounds, target) in [(3, 15), (3, 12), (1, 9), (10, 10), (100, 543), (0, 0)] {
+            let guide = build_guide(&rules, Reading::Shapes, rounds, target).unwrap();
+            assert_eq!(guide.len(), rounds);
+            assert_eq!(
+                score_shapes(&rules, to_text(&guide).as_bytes()).unwrap(),
+                target
+            );
+
+            let guide = build_guide(&rules, Reading::Outcomes, rounds, target).unwrap();
+            assert_eq!(
+                score_outcomes(&rules, to_text(&guide).as_bytes()).unwrap(),
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn test_impossible_scores() {
+        let rules = Rules::default();
+
+        assert_eq!(
+            build_guide(&rules, Reading::Shapes, 2, 19),
+            Err(Impossible::OutOfRange {
+                lowest: 2,
+                highest: 18
+            })
+        );
+
+        // Every round scores an even number
+        let rules = Rules::from_toml(
+            "shape_scores = [2, 4, 6]\noutcome_scores = { loss = 0, draw = 2, win = 4 }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            build_guide(&rules, Reading::Outcomes, 3, 13),
+            Err(Impossible::Unreachable)
+        );
+        assert!(build_guide(&rules, Reading::Outcomes, 3, 14).is_ok());
+    }
+
+    #[test]
+    fn test_too_many_rounds() {
+        let rules = Rules::default();
+
+        assert_eq!(
+            build_guide(&rules, Reading::Shapes, usize::MAX, 0),
+            Err(Impossible::TooManyRounds)
+        );
+    }
+
+    #[test]
+    fn test_large_scores() {
+        // Scaled up, the puzzle's rules are searched in units of the scale
+        let rules = Rules::from_toml(
+            "shape_scores = [100000000, 200000000, 300000000]\n\
+             outcome_scores = { loss = 0, draw = 300000000, win = 600000000 }",
+        )
+        .unwrap();
+        let guide = build_guide(&rules, Reading::Shapes, 1000, 5_432 * 100_000_000).unwrap();
+        let score = guide
+            .iter()
+            .map(|(opponent, me)| score_line(&rules, Reading::Shapes, *opponent, *me))
+            .sum::<u64>();
+        assert_eq!(guide.len(), 1000);
+        assert_eq!(score, 5_432 * 100_000_000);
+
+        // Too far apart to search, without running out of memory
+        let rules = Rules::from_toml("shape_scores = [1, 2, 100000000]").unwrap();
+        assert_eq!(
+            build_guide(&rules, Reading::Shapes, 1000, 50_000_000_000),
+            Err(Impossible::TooFarApart)
+        );
+    }
+
+    #[test]
+    fn test_too_few_rounds() {
+        // Rounds score 1, 3, 4 or 6, so 9 more than the lowest takes three rounds above it
+        let rules = Rules::from_toml(
+            "shape_scores = [1, 1, 3]\noutcome_scores = { loss = 0, draw = 0, win = 3 }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            build_guide(&rules, Reading::Shapes, 2, 2 + 9),
+            Err(Impossible::Unreachable)
+        );
+        assert!(build_guide(&rules, Reading::Shapes, 3, 3 + 9).is_ok());
+    }
+
+    #[test]
+    fn test_many_rounds() {
+        let rules = Rules::default();
+        let rounds = 100_000;
+        let guide = build_guide(&rules, Reading::Shapes, rounds, 899_999).unwrap();
+
+        assert_eq!(guide.len(), rounds);
+        assert_eq!(
+            score_shapes(&rules, to_text(&guide).as_bytes()).unwrap(),
+            899_999
+        );
+        assert_eq!(
+            build_guide(&rules, Reading::Shapes, rounds, 900_000).unwrap(),
+            vec![('B', 'Z'); rounds]
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use std::fmt::{self, Display};
+
+use serde::Deserialize;
+
+use crate::game::{get_outcome, Game, Outcome, RulesError, Shape};
+
+/// How a strategy guide is read and scored: the game, what each shape and outcome scores, and
+/// the symbols standing for them in each column of the guide.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    pub game: Game,
+    shape_scores: Vec<u32>,
+    outcome_scores: OutcomeScores,
+    symbols: Symbols,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutcomeScores {
+    pub loss: u32,
+    pub draw: u32,
+    pub win: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Symbols {
+    /// The symbol of each shape in the first column.
+    opponent: Vec<char>,
+    /// The symbol of each shape in the second column, when it's read as shapes.
+    me: Vec<char>,
+    /// The symbols of a loss, a draw and a win in the second column, when it's read as outcomes.
+    outcomes: [char; 3],
+}
+
+/// A rules file. Anything left out is as in the puzzle.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    shapes: Option<Vec<String>>,
+    /// (winner, loser) pairs, each shape beating half of the others. Without them, each shape
+    /// beats the half of the shapes listed just before it.
+    beats: Option<Vec<(String, String)>>,
+    shape_scores: Option<Vec<u32>>,
+    outcome_scores: Option<OutcomeScores>,
+    #[serde(default)]
+    symbols: SymbolsFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SymbolsFile {
+    opponent: Option<String>,
+    me: Option<String>,
+    outcomes: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LoadError {
+    Syntax(String),
+    Game(RulesError),
+    Invalid(String),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Syntax(message) | LoadError::Invalid(message) => write!(f, "{}", message),
+            LoadError::Game(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<RulesError> for LoadError {
+    fn from(error: RulesError) -> Self {
+        LoadError::Game(error)
+    }
+}
+
+impl Default for Rules {
+    /// The puzzle's rules: Rock, Paper and Scissors scoring 1, 2 and 3, written A, B and C by
+    /// the opponent and X, Y and Z by me, and outcomes scoring 0, 3 and 6 written X, Y and Z.
+    fn default() -> Self {
+        Self::for_game(Game::rock_paper_scissors()).expect("Three shapes can be lettered")
+    }
+}
+
+impl Rules {
+    /// Scores the shapes from 1 in the order they're listed, and the outcomes as in the puzzle.
+    /// The opponent's shapes are lettered from A, and mine so that the last one is Z, which
+    /// fails for games of more than 26 shapes.
+    pub fn for_game(game: Game) -> Result<Self, RulesError> {
+        let count = game.shape_count();
+        let opponent = opponent_letters(count)?;
+        let me = my_letters(count)?;
+
+        Ok(Self::with_symbols(game, opponent, me))
+    }
+
+    fn with_symbols(game: Game, opponent: Vec<char>, me: Vec<char>) -> Self {
+        Self {
+            shape_scores: (1..=game.shape_count() as u32).collect(),
+            outcome_scores: OutcomeScores {
+                loss: 0,
+                draw: 3,
+                win: 6,
+            },
+            symbols: Symbols {
+                opponent,
+                me,
+                outcomes: ['X', 'Y', 'Z'],
+            },
+            game,
+        }
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, LoadError> {
+        let file = toml::from_str::<RulesFile>(text)
+            .map_err(|error| LoadError::Syntax(error.message().to_string()))?;
+
+        let game = match (&file.shapes, &file.beats) {
+            (None, None) => Game::rock_paper_scissors(),
+            (Some(shapes), None) => {
+                Game::cyclic(&shapes.iter().map(String::as_str).collect::<Vec<_>>())?
+            }
+            (shapes, Some(beats)) => {
+                let shapes = match shapes {
+                    Some(shapes) => shapes.iter().map(String::as_str).collect(),
+                    None => vec!["Rock", "Paper", "Scissors"],
+                };
+                let beats = beats
+                    .iter()
+                    .map(|(winner, loser)| (winner.as_str(), loser.as_str()))
+                    .collect::<Vec<_>>();
+
+                Game::from_rules(&shapes, &beats)?
+            }
+        };
+
+        // The shapes are only lettered when their symbols aren't given, as there may be too many
+        let count = game.shape_count();
+        let opponent = match file.symbols.opponent {
+            Some(opponent) => symbols("opponent", &opponent, count)?,
+            None => opponent_letters(count)?,
+        };
+        let me = match file.symbols.me {
+            Some(me) => symbols("me", &me, count)?,
+            None => my_letters(count)?,
+        };
+        let mut rules = Self::with_symbols(game, opponent, me);
+
+        if let Some(shape_scores) = file.shape_scores {
+            if shape_scores.len() != count {
+                return Err(LoadError::Invalid(format!(
+                    "Expected a score for each of the {} shapes",
+                    count
+                )));
+            }
+
+            rules.shape_scores = shape_scores;
+        }
+
+        if let Some(outcome_scores) = file.outcome_scores {
+            rules.outcome_scores = outcome_scores;
+        }
+
+        if let Some(outcomes) = file.symbols.outcomes {
+            let outcomes = symbols("outcomes", &outcomes, 3)?;
+            rules.symbols.outcomes = [outcomes[0], outcomes[1], outcomes[2]];
+        }
+
+        Ok(rules)
+    }
+
+    pub fn opponent_symbols(&self) -> &[char] {
+        &self.symbols.opponent
+    }
+
+    pub fn my_symbols(&self) -> &[char] {
+        &self.symbols.me
+    }
+
+    /// The symbols of a loss, a draw and a win.
+    pub fn outcome_symbols(&self) -> &[char] {
+        &self.symbols.outcomes
+    }
+
+    pub fn opponent_shape(&self, symbol: char) -> Option<Shape> {
+        let position = self.symbols.opponent.iter().position(|s| *s == symbol)?;
+        Some(Shape(position))
+    }
+
+    pub fn my_shape(&self, symbol: char) -> Option<Shape> {
+        let position = self.symbols.me.iter().position(|s| *s == symbol)?;
+        Some(Shape(position))
+    }
+
+    pub fn outcome(&self, symbol: char) -> Option<Outcome> {
+        let position = self.symbols.outcomes.iter().position(|s| *s == symbol)?;
+        Some([Outcome::Loss, Outcome::Draw, Outcome::Win][position])
+    }
+}
+
+/// Letters from A.
+fn opponent_letters(count: usize) -> Result<Vec<char>, RulesError> {
+    letters(0, count)
+}
+
+/// Letters ending with Z.
+fn my_letters(count: usize) -> Result<Vec<char>, RulesError> {
+    let skipped = 26_usize
+        .checked_sub(count)
+        .ok_or(RulesError::NoDefaultSymbols(count))?;
+
+    letters(skipped, count)
+}
+
+fn letters(skipped: usize, count: usize) -> Result<Vec<char>, RulesError> {
+    let letters = ('A'..='Z').skip(skipped).take(count).collect::<Vec<_>>();
+
+    if letters.len() < count {
+        return Err(RulesError::NoDefaultSymbols(count));
+    }
+
+    Ok(letters)
+}
+
+/// Reads the symbols of a column, one character each.
+fn symbols(column: &str, text: &str, count: usize) -> Result<Vec<char>, LoadError> {
+    let symbols = text.chars().collect::<Vec<_>>();
+
+    if symbols.len() != count {
+        return Err(LoadError::Invalid(format!(
+            "Expected {} symbols for {}, got '{}'",
+            count, column, text
+        )));
+    }
+
+    if let Some(symbol) = symbols
+        .iter()
+        .enumerate()
+        .find_map(|(i, symbol)| symbols[..i].contains(symbol).then_some(symbol))
+    {
+        return Err(LoadError::Invalid(format!(
+            "Symbol '{}' stands for two things in {}",
+            symbol, column
+        )));
+    }
+
+    Ok(symbols)
+}
+
+/// The two scores are added as `u64`, so that no configured scores overflow.
+pub fn get_round_score(rules: &Rules, my_shape: &Shape, others_shape: &Shape) -> u64 {
+    let outcome = get_outcome(&rules.game, my_shape, others_shape);
+
+    u64::from(get_shape_score(rules, my_shape)) + u64::from(get_outcome_score(rules, &outcome))
+}
+
+pub fn get_shape_score(rules: &Rules, shape: &Shape) -> u32 {
+    rules.shape_scores[shape.0]
+}
+
+pub fn get_outcome_score(rules: &Rules, outcome: &Outcome) -> u32 {
+    match outcome {
+        Outcome::Loss => rules.outcome_scores.loss,
+        Outcome::Draw => rules.outcome_scores.draw,
+        Outcome::Win => rules.outcome_scores.win,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules() {
+        let rules = Rules::default();
+
+        assert_eq!(rules.opponent_shape('B'), Some(Shape(1)));
+        assert_eq!(rules.my_shape('Z'), Some(Shape(2)));
+        assert_eq!(rules.my_shape('A'), None);
+        assert_eq!(rules.outcome('X'), Some(Outcome::Loss));
+        assert_eq!(get_round_score(&rules, &Shape(1), &Shape(0)), 8);
+        assert_eq!(get_round_score(&rules, &Shape(0), &Shape(1)), 1);
+        assert_eq!(get_round_score(&rules, &Shape(2), &Shape(2)), 6);
+
+        let rules = Rules::for_game(Game::rock_paper_scissors_lizard_spock()).unwrap();
+        assert_eq!(rules.my_shape('Z'), Some(Shape(4)));
+        assert_eq!(get_round_score(&rules, &Shape(4), &Shape(2)), 11);
+    }
+
+    #[test]
+    fn test_rules_file_matches_default() {
+        let text = std::fs::read_to_string("rules.toml").unwrap();
+        let rules = Rules::from_toml(&text).unwrap();
+        let default = Rules::default();
+
+        assert_eq!(rules.shape_scores, default.shape_scores);
+        assert_eq!(rules.outcome_scores, default.outcome_scores);
+        assert_eq!(rules.symbols, default.symbols);
+
+        for shape in default.game.shapes() {
+            for other in default.game.shapes() {
+                assert_eq!(
+                    rules.game.beats(&shape, &other),
+                    default.game.beats(&shape, &other)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let rules = Rules::from_toml(
+            r#"
+            shape_scores = [10, 20, 30]
+            outcome_scores = { loss = 1, draw = 2, win = 3 }
+
+            [symbols]
+            opponent = "RPS"
+            me = "rps"
+            outcomes = "LDW"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(rules.opponent_shape('S'), Some(Shape(2)));
+        assert_eq!(rules.my_shape('p'), Some(Shape(1)));
+        assert_eq!(rules.outcome('W'), Some(Outcome::Win));
+        assert_eq!(get_round_score(&rules, &Shape(1), &Shape(0)), 23);
+
+        let rules = Rules::from_toml("shapes = ['A', 'B', 'C', 'D', 'E']").unwrap();
+        assert_eq!(rules.game.shape_count(), 5);
+        assert_eq!(rules.my_shape('V'), Some(Shape(0)));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(matches!(
+            Rules::from_toml("shape_scores = 3"),
+            Err(LoadError::Syntax(_))
+        ));
+        assert!(matches!(
+            Rules::from_toml("shape_scores = [1, 2]"),
+            Err(LoadError::Invalid(_))
+        ));
+        assert!(matches!(
+            Rules::from_toml("[symbols]\nme = 'XXZ'"),
+            Err(LoadError::Invalid(_))
+        ));
+        assert_eq!(
+            Rules::from_toml("shapes = ['A', 'B']").unwrap_err(),
+            LoadError::Game(RulesError::EvenShapeCount(2))
+        );
+        assert!(matches!(
+            Rules::from_toml("unknown = 1"),
+            Err(LoadError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn test_symbols_of_many_shapes() {
+        let names = |count: usize| {
+            (0..count)
+                .map(|i| format!("'S{}'", i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        assert!(Rules::from_toml(&format!("shapes = [{}]", names(25))).is_ok());
+
+        for count in [27, 93, 257] {
+            assert_eq!(
+                Rules::from_toml(&format!("shapes = [{}]", names(count))).unwrap_err(),
+                LoadError::Game(RulesError::NoDefaultSymbols(count))
+            );
+        }
+
+        // Given symbols are enough, without lettering the shapes
+        let symbols = ('a'..).take(27).collect::<String>();
+        let rules = Rules::from_toml(&format!(
+            "shapes = [{}]\n[symbols]\nopponent = '{}'\nme = '{}'",
+            names(27),
+            symbols,
+            symbols.to_uppercase()
+        ))
+        .unwrap();
+        assert_eq!(rules.my_shape('A'), Some(Shape(0)));
+        assert!(Rules::for_game(rules.game).is_err());
+    }
+}
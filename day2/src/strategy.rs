@@ -0,0 +1,160 @@
+use std::cmp::Reverse;
+
+use aoc::rng::Rng;
+
+use crate::game::{get_shape_required_for_outcome, Game, Outcome, Shape};
+
+/// A way of playing a match, one round at a time.
+pub trait Strategy {
+    /// Chooses the shape to play, given the rounds played so far as (my shape, their shape).
+    fn choose(&mut self, game: &Game, history: &[(Shape, Shape)]) -> Shape;
+}
+
+/// Always plays the same shape.
+pub struct Constant(pub Shape);
+
+impl Strategy for Constant {
+    fn choose(&mut self, _game: &Game, _history: &[(Shape, Shape)]) -> Shape {
+        self.0
+    }
+}
+
+/// Plays any shape with the same chance, the same way for the same seed.
+pub struct Random {
+    rng: Rng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Strategy for Random {
+    fn choose(&mut self, game: &Game, _history: &[(Shape, Shape)]) -> Shape {
+        Shape(self.rng.below(game.shape_count() as u64) as usize)
+    }
+}
+
+/// Plays the shapes in the game's order, starting over after the last one.
+pub struct Cycle;
+
+impl Strategy for Cycle {
+    fn choose(&mut self, game: &Game, history: &[(Shape, Shape)]) -> Shape {
+        Shape(history.len() % game.shape_count())
+    }
+}
+
+/// Plays whatever beats the shape the opponent has played the most so far, taking the first of
+/// them in the game's order on a tie.
+#[derive(Default)]
+pub struct FrequencyCounter {
+    /// How often the opponent played each shape in the rounds counted so far.
+    counts: Vec<usize>,
+    counted: usize,
+}
+
+impl Strategy for FrequencyCounter {
+    fn choose(&mut self, game: &Game, history: &[(Shape, Shape)]) -> Shape {
+        // Only the rounds played since the last choice are counted, unless a new match started
+        if self.counted > history.len() || self.counts.len() != game.shape_count() {
+            self.counts = vec![0; game.shape_count()];
+            self.counted = 0;
+        }
+
+        for (_, theirs) in &history[self.counted..] {
+            self.counts[theirs.0] += 1;
+        }
+
+        self.counted = history.len();
+
+        let most_played = game
+            .shapes()
+            .max_by_key(|shape| (self.counts[shape.0], Reverse(*shape)))
+            .unwrap();
+
+        get_shape_required_for_outcome(game, &most_played, &Outcome::Win)
+    }
+}
+
+/// Plays the shapes of a strategy guide in order, starting over at the end of the guide.
+pub struct FollowGuide {
+    shapes: Vec<Shape>,
+}
+
+impl FollowGuide {
+    /// Panics if the guide is empty.
+    pub fn new(shapes: Vec<Shape>) -> Self {
+        assert!(!shapes.is_empty(), "The guide has no rounds to follow");
+        Self { shapes }
+    }
+}
+
+impl Strategy for FollowGuide {
+    fn choose(&mut self, _game: &Game, history: &[(Shape, Shape)]) -> Shape {
+        self.shapes[history.len() % self.shapes.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(strategy: &mut dyn Strategy, game: &Game, theirs: &[Shape]) -> Vec<Shape> {
+        let mut history = Vec::new();
+
+        for their_shape in theirs {
+            let my_shape = strategy.choose(game, &history);
+            history.push((my_shape, *their_shape));
+        }
+
+        history.into_iter().map(|(mine, _)| mine).collect()
+    }
+
+    #[test]
+    fn test_strategies() {
+        let game = Game::rock_paper_scissors();
+        let theirs = [Shape(0), Shape(2), Shape(2), Shape(1)];
+
+        assert_eq!(play(&mut Constant(Shape(1)), &game, &theirs), [Shape(1); 4]);
+        assert_eq!(
+            play(&mut Cycle, &game, &theirs),
+            [Shape(0), Shape(1), Shape(2), Shape(0)]
+        );
+        // Rock is the most played until Scissors is played twice
+        assert_eq!(
+            play(&mut FrequencyCounter::default(), &game, &theirs),
+            [Shape(1), Shape(1), Shape(1), Shape(0)]
+        );
+        assert_eq!(
+            play(
+                &mut FollowGuide::new(vec![Shape(2), Shape(0)]),
+                &game,
+                &theirs
+            ),
+            [Shape(2), Shape(0), Shape(2), Shape(0)]
+        );
+    }
+
+    #[test]
+    fn test_frequency_counter_starts_over() {
+        let game = Game::rock_paper_scissors();
+        let mut counter = FrequencyCounter::default();
+        let first = play(&mut counter, &game, &[Shape(2); 5]);
+
+        assert_eq!(first, [Shape(1), Shape(0), Shape(0), Shape(0), Shape(0)]);
+        assert_eq!(play(&mut counter, &game, &[Shape(2); 5]), first);
+    }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let theirs = [Shape(0); 100];
+        let shapes = play(&mut Random::new(3), &game, &theirs);
+
+        assert_eq!(shapes, play(&mut Random::new(3), &game, &theirs));
+        assert!(game.shapes().all(|shape| shapes.contains(&shape)));
+    }
+}
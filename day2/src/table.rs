@@ -0,0 +1,113 @@
+/// The score of each of the 9 rounds a line of the puzzle's guide can hold, for scoring a guide
+/// without parsing it.
+pub struct ScoreTable {
+    /// Indexed by the opponent's letter from A in the upper two bits and mine from X in the lower
+    /// two, so that any index is in bounds.
+    scores: [u64; 16],
+}
+
+impl ScoreTable {
+    /// Builds the table by scoring each possible line, e.g. "B Z", with the given function.
+    pub fn new(score_line: impl Fn(&str) -> u64) -> Self {
+        let mut scores = [0; 16];
+
+        for opponent in 0..3 {
+            for me in 0..3 {
+                let line = format!("{} {}", (b'A' + opponent) as char, (b'X' + me) as char);
+                scores[index(opponent, me)] = score_line(&line);
+            }
+        }
+
+        Self { scores }
+    }
+
+    /// Scores a guide four bytes a line at a time, without branching on the rounds. None if any
+    /// line isn't a round, in which case `check` points out where.
+    pub fn score(&self, input: &[u8]) -> Option<u64> {
+        // The last line may be missing its line ending
+        let (lines, last) = match input.len() % 4 {
+            0 => (input, None),
+            3 => {
+                let (lines, last) = input.split_at(input.len() - 3);
+                (lines, Some(last))
+            }
+            _ => return None,
+        };
+
+        let mut total = 0_u64;
+        let mut invalid = false;
+        let mut add = |line: [u8; 4]| {
+            let (score, valid) = self.score_line(u32::from_le_bytes(line));
+            total += score;
+            invalid |= !valid;
+        };
+
+        for line in lines.chunks_exact(4) {
+            add([line[0], line[1], line[2], line[3]]);
+        }
+
+        if let Some(line) = last {
+            add([line[0], line[1], line[2], b'\n']);
+        }
+
+        (!invalid).then_some(total)
+    }
+
+    /// Scores a line read as a little-endian word, and tells whether it is a round.
+    fn score_line(&self, line: u32) -> (u64, bool) {
+        let opponent = (line as u8).wrapping_sub(b'A');
+        let me = ((line >> 16) as u8).wrapping_sub(b'X');
+        // The separator and line ending are checked at once
+        let valid = (opponent < 3) & (me < 3) & (line & 0xff00_ff00 == 0x0a00_2000);
+
+        (self.scores[index(opponent, me)], valid)
+    }
+}
+
+fn index(opponent: u8, me: u8) -> usize {
+    (((opponent & 3) << 2) | (me & 3)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{generate_input, rules::Rules, score_outcomes, score_shapes};
+
+    use super::*;
+
+    #[test]
+    fn test_matches_parsing() {
+        let rules = Rules::default();
+        let shapes = ScoreTable::new(|line| score_shapes(&rules, line.as_bytes()).unwrap());
+        let outcomes = ScoreTable::new(|line| score_outcomes(&rules, line.as_bytes()).unwrap());
+
+        for size in [0, 1, 10, 1_000] {
+            let input = generate_input(size);
+
+            assert_eq!(
+                shapes.score(input.as_bytes()),
+                score_shapes(&rules, input.as_bytes()).ok()
+            );
+            assert_eq!(
+                outcomes.score(input.as_bytes()),
+                score_outcomes(&rules, input.as_bytes()).ok()
+            );
+        }
+
+        assert_eq!(shapes.score(b"A Y\nB X\nC Z"), Some(15));
+    }
+
+    #[test]
+    fn test_rejects_invalid_lines() {
+        let table = ScoreTable::new(|_| 1);
+
+        for input in [
+            &b"A Y\nD X\n"[..],
+            b"A Y\nB W\n",
+            b"A  Y\n",
+            b"AY\n",
+            b"A Y\r\n",
+        ] {
+            assert_eq!(table.score(input), None);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::cmp::{Ordering, Reverse};
+
+use crate::{
+    rules::{get_round_score, Rules},
+    strategy::Strategy,
+};
+
+/// A strategy entered in a tournament. Each match is played by a fresh strategy, so none of
+/// them carries what it learned about one opponent into the next match.
+pub struct Entrant {
+    pub name: String,
+    make: Box<dyn Fn() -> Box<dyn Strategy>>,
+}
+
+impl Entrant {
+    pub fn new<S: Strategy + 'static>(name: &str, make: impl Fn() -> S + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            make: Box::new(move || Box::new(make())),
+        }
+    }
+}
+
+/// How an entrant did over all of its matches.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Standing {
+    pub name: String,
+    pub points: u64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+/// Plays a match between two strategies, returning the points each of them scored.
+pub fn play_match(
+    rules: &Rules,
+    first: &mut dyn Strategy,
+    second: &mut dyn Strategy,
+    rounds: usize,
+) -> (u64, u64) {
+    let mut first_history = Vec::new();
+    let mut second_history = Vec::new();
+    let mut points = (0, 0);
+
+    for _ in 0..rounds {
+        let first_shape = first.choose(&rules.game, &first_history);
+        let second_shape = second.choose(&rules.game, &second_history);
+
+        points.0 += get_round_score(rules, &first_shape, &second_shape);
+        points.1 += get_round_score(rules, &second_shape, &first_shape);
+
+        first_history.push((first_shape, second_shape));
+        second_history.push((second_shape, first_shape));
+    }
+
+    points
+}
+
+/// Plays a match of the given number of rounds between every pair of entrants, and ranks them
+/// by the points they scored in all of their matches.
+pub fn round_robin(rules: &Rules, entrants: &[Entrant], rounds: usize) -> Vec<Standing> {
+    let mut standings = entrants
+        .iter()
+        .map(|entrant| Standing {
+            name: entrant.name.clone(),
+            points: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        })
+        .collect::<Vec<_>>();
+
+    for i in 0..entrants.len() {
+        for j in i + 1..entrants.len() {
+            let (first, second) = play_match(
+                rules,
+                (entrants[i].make)().as_mut(),
+                (entrants[j].make)().as_mut(),
+                rounds,
+            );
+
+            standings[i].points += first;
+            standings[j].points += second;
+
+            match first.cmp(&second) {
+                Ordering::Greater => {
+                    standings[i].wins += 1;
+                    standings[j].losses += 1;
+                }
+                Ordering::Equal => {
+                    standings[i].draws += 1;
+                    standings[j].draws += 1;
+                }
+                Ordering::Less => {
+                    standings[i].losses += 1;
+                    standings[j].wins += 1;
+                }
+            }
+        }
+    }
+
+    // Entrants with as many points keep the order they were entered in
+    standings.sort_by_key(|standing| Reverse(standing.points));
+
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        game::Shape,
+        strategy::{Constant, Cycle, FrequencyCounter},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_play_match() {
+        let rules = Rules::default();
+        let points = play_match(&rules, &mut Constant(Shape(0)), &mut Constant(Shape(1)), 10);
+
+        assert_eq!(points, (10, 80));
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let rules = Rules::default();
+        let entrants = [
+            Entrant::new("Rock", || Constant(Shape(0))),
+            Entrant::new("Cycle", || Cycle),
+            Entrant::new("Frequency", FrequencyCounter::default),
+        ];
+        let standings = round_robin(&rules, &entrants, 9);
+
+        assert_eq!(
+            standings
+                .iter()
+                .map(|standing| standing.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Frequency", "Cycle", "Rock"]
+        );
+        assert_eq!(
+            standings[0],
+            Standing {
+                name: "Frequency".to_string(),
+                // Paper beats Rock every round, while the cycle stays a step ahead of the
+                // counts, so that match is drawn 45 to 45
+                points: 9 * 8 + 45,
+                wins: 1,
+                draws: 1,
+                losses: 0,
+            }
+        );
+    }
+}
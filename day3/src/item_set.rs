@@ -0,0 +1,95 @@
+use std::ops::{BitAnd, BitOr};
+
+use crate::get_priority;
+
+/// A set of item kinds, one bit each by priority, so sets are copied and combined without
+/// allocating.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    /// Every item kind, a-z and A-Z.
+    pub const ALL: ItemSet = ItemSet(((1 << 52) - 1) << 1);
+
+    /// The priorities of the items, lowest first.
+    pub fn priorities(self) -> impl Iterator<Item = u8> {
+        let mut bits = self.0;
+
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+
+            let priority = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+            Some(priority)
+        })
+    }
+
+    /// The items, in order of priority.
+    pub fn items(self) -> impl Iterator<Item = u8> {
+        self.priorities().map(|priority| {
+            if priority <= 26 {
+                b'a' + priority - 1
+            } else {
+                b'A' + priority - 27
+            }
+        })
+    }
+}
+
+/// Collects items, which have to be letters.
+impl FromIterator<u8> for ItemSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(items: I) -> Self {
+        Self(
+            items
+                .into_iter()
+                .fold(0, |bits, item| bits | 1 << get_priority(item)),
+        )
+    }
+}
+
+/// The intersection.
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+}
+
+/// The union.
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_set() {
+        let first = b"vJrwpWtwJgWr".iter().copied().collect::<ItemSet>();
+        let second = b"hcsFMMfFFhFp".iter().copied().collect::<ItemSet>();
+
+        assert_eq!(first.items().collect::<Vec<_>>(), b"gprtvwJW");
+        assert_eq!((first & second).items().collect::<Vec<_>>(), b"p");
+        assert_eq!((first & second).priorities().collect::<Vec<_>>(), [16]);
+        assert_eq!((first | second).items().count(), 14);
+        assert_eq!(ItemSet::default().items().next(), None);
+    }
+
+    #[test]
+    fn test_all_items() {
+        let items = ItemSet::ALL.items().collect::<Vec<_>>();
+
+        assert_eq!(items.len(), 52);
+        assert_eq!(items.iter().copied().collect::<ItemSet>(), ItemSet::ALL);
+        assert_eq!(items[0], b'a');
+        assert_eq!(items[51], b'Z');
+    }
+}
@@ -1,64 +1,193 @@
+mod item_set;
+
 use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
+    collections::HashSet,
+    fmt::{self, Display},
+    io::BufRead,
 };
 
-fn main() {
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
+use aoc::{rng::Rng, Bencher, Day, Line, Part, Violation};
+use item_set::ItemSet;
+
+/// Sizes of the generated inputs, in groups of three rucksacks.
+const BENCHMARK_SIZES: &[usize] = &[1_000, 10_000, 100_000];
 
-    let answer_one = solve_part_one(reader);
-    println!("Answer 1: {}", answer_one);
+fn main() {
+    Day::new()
+        .solution(Part::One, "item set", |input| {
+            solve_part_one(input).to_string()
+        })
+        .solution(Part::One, "hash set", |input| {
+            solve_part_one_hash_set(input).to_string()
+        })
+        .fallible_solution(Part::Two, "item set", |input| {
+            solve_part_two(input)
+                .map(|sum| sum.to_string())
+                .map_err(|error| error.to_string())
+        })
+        .fallible_solution(Part::Two, "hash set", |input| {
+            solve_part_two_hash_set(input)
+                .map(|sum| sum.to_string())
+                .map_err(|error| error.to_string())
+        })
+        .grammar(check_input)
+        .benchmark(
+            "get_priority",
+            &[1_000, 10_000, 100_000],
+            bench_get_priority,
+        )
+        .benchmark("part one (item set)", BENCHMARK_SIZES, |bencher, size| {
+            let input = generate_input(size);
+            bencher.iter(|| solve_part_one(input.as_bytes()));
+        })
+        .benchmark("part one (hash set)", BENCHMARK_SIZES, |bencher, size| {
+            let input = generate_input(size);
+            bencher.iter(|| solve_part_one_hash_set(input.as_bytes()));
+        })
+        .benchmark("part two (item set)", BENCHMARK_SIZES, |bencher, size| {
+            let input = generate_input(size);
+            bencher.iter(|| solve_part_two(input.as_bytes()).unwrap());
+        })
+        .benchmark("part two (hash set)", BENCHMARK_SIZES, |bencher, size| {
+            let input = generate_input(size);
+            bencher.iter(|| solve_part_two_hash_set(input.as_bytes()).unwrap());
+        })
+        .command("badges", "GROUP_SIZE", print_badges)
+        .run();
+}
 
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
+#[derive(Debug, PartialEq, Eq)]
+enum GroupError {
+    /// The lines left over at the end are too few to make up a group.
+    Incomplete { first_line: usize, rucksacks: usize },
+    /// No item is in every rucksack of the group starting at the line.
+    NoSharedItem { first_line: usize },
+}
 
-    let answer_two = solve_part_two(reader);
-    println!("Answer 2: {}", answer_two);
+impl Display for GroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupError::Incomplete {
+                first_line,
+                rucksacks,
+            } => write!(
+                f,
+                "The last group, from line {}, only has {} rucksacks",
+                first_line, rucksacks
+            ),
+            GroupError::NoSharedItem { first_line } => {
+                write!(f, "The group from line {} shares no item", first_line)
+            }
+        }
+    }
 }
 
 fn solve_part_one<R: BufRead>(input: R) -> u32 {
+    input
+        .lines()
+        .map(|line| find_shared_priorities(line.unwrap().as_bytes()))
+        .sum()
+}
+
+fn solve_part_one_hash_set<R: BufRead>(input: R) -> u32 {
     input
         .lines()
         .map(|line| find_shared_priorities_from_line(&line.unwrap()))
         .sum()
 }
 
-fn solve_part_two<R: BufRead>(input: R) -> u32 {
-    let lines = input.lines().map(|line| line.unwrap());
+fn solve_part_two<R: BufRead>(input: R) -> Result<u32, GroupError> {
+    sum_group_priorities(input, 3, find_shared_item)
+}
 
-    let mut group = vec![String::new(); 3];
-    let mut priority_sum = 0_u32;
+fn solve_part_two_hash_set<R: BufRead>(input: R) -> Result<u32, GroupError> {
+    sum_group_priorities(input, 3, find_shared_item_in_group)
+}
+
+/// Sums the priorities of the items shared by each group of the given number of rucksacks.
+/// Lines left over at the end are an error, rather than left out of the sum.
+fn sum_group_priorities<R: BufRead>(
+    input: R,
+    group_size: usize,
+    find_shared_item: fn(&[String]) -> Option<u8>,
+) -> Result<u32, GroupError> {
+    assert!(group_size > 0, "A group needs at least one rucksack");
 
-    for (i, line) in lines.enumerate() {
-        let group_index = i % 3;
+    let mut group = Vec::with_capacity(group_size);
+    let mut line_count = 0;
+    let mut priority_sum = 0_u32;
 
-        group[group_index] = line;
+    for line in input.lines() {
+        group.push(line.unwrap());
+        line_count += 1;
 
-        if group_index == 2 {
-            let shared_item = find_shared_item_in_group(&group);
+        if group.len() == group_size {
+            let shared_item = find_shared_item(&group).ok_or(GroupError::NoSharedItem {
+                first_line: line_count + 1 - group_size,
+            })?;
             priority_sum += get_priority(shared_item) as u32;
+            group.clear();
         }
     }
 
-    priority_sum
+    if !group.is_empty() {
+        return Err(GroupError::Incomplete {
+            first_line: line_count + 1 - group.len(),
+            rucksacks: group.len(),
+        });
+    }
+
+    Ok(priority_sum)
+}
+
+/// Finds the item in every rucksack of the group, the lowest priority one if there are several.
+fn find_shared_item(group: &[String]) -> Option<u8> {
+    let shared = group
+        .iter()
+        .map(|rucksack| rucksack.bytes().collect::<ItemSet>())
+        .fold(ItemSet::ALL, |shared, items| shared & items);
+
+    shared.items().next()
 }
 
-fn find_shared_item_in_group(group: &[String]) -> u8 {
+/// Sums the priorities of the items in both compartments of a rucksack.
+fn find_shared_priorities(rucksack: &[u8]) -> u32 {
+    let (compartment_1, compartment_2) = rucksack.split_at(rucksack.len() / 2);
+    let shared = compartment_1.iter().copied().collect::<ItemSet>()
+        & compartment_2.iter().copied().collect::<ItemSet>();
+
+    shared.priorities().map(|priority| priority as u32).sum()
+}
+
+fn find_shared_item_in_group(group: &[String]) -> Option<u8> {
     let first_list = &group[0];
     let other_lists = group[1..]
         .iter()
         .map(|list| -> HashSet<u8> { HashSet::from_iter(list.bytes()) })
         .collect::<Vec<_>>();
 
-    for byte in first_list.bytes() {
-        if other_lists.iter().all(|list| list.contains(&byte)) {
-            return byte;
-        }
-    }
+    first_list
+        .bytes()
+        .filter(|byte| other_lists.iter().all(|list| list.contains(byte)))
+        .min_by_key(|byte| get_priority(*byte))
+}
+
+/// Sums the priorities of the items shared by groups of another size, e.g. `day3 badges 2`.
+fn print_badges(input: &[u8], args: &[String]) -> Result<(), String> {
+    let group_size = match args {
+        [size] => size
+            .parse::<usize>()
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or_else(|| format!("Expected a group size of at least 1, got '{}'", size))?,
+        _ => return Err("Expected the number of rucksacks in a group".to_string()),
+    };
+
+    let sum = sum_group_priorities(input, group_size, find_shared_item)
+        .map_err(|error| error.to_string())?;
+    println!("{}", sum);
 
-    panic!("Found no shared item");
+    Ok(())
 }
 
 fn find_shared_priorities_from_line(line: &str) -> u32 {
@@ -93,6 +222,102 @@ fn get_priority(byte: u8) -> u8 {
     }
 }
 
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for line in lines {
+        if let Some(index) = line.text.find(|c: char| !c.is_ascii_alphabetic()) {
+            violations.push(Violation::new(
+                line,
+                index + 1,
+                "Expected an item letter",
+                "remove the character, items are a-z and A-Z",
+            ));
+        } else if line.text.is_empty() {
+            violations.push(Violation::new(
+                line,
+                1,
+                "Blank line instead of a rucksack",
+                "remove the blank line",
+            ));
+        } else if !line.text.len().is_multiple_of(2) {
+            violations.push(Violation::new(
+                line,
+                line.text.len(),
+                "Rucksack has an odd number of items, so can't be split into compartments",
+                "check the line for a missing or extra item",
+            ));
+        }
+    }
+
+    if !lines.len().is_multiple_of(3) {
+        if let Some(last) = lines.last() {
+            violations.push(Violation::new(
+                last,
+                1,
+                "Last group doesn't have three rucksacks",
+                "add the missing rucksacks, the input may be cut short",
+            ));
+        }
+    }
+
+    violations
+}
+
+fn bench_get_priority(bencher: &mut Bencher, size: usize) {
+    let mut rng = Rng::new(3);
+    let items = (0..size)
+        .map(|_| {
+            let letter = rng.below(52) as u8;
+
+            if letter < 26 {
+                b'a' + letter
+            } else {
+                b'A' + letter - 26
+            }
+        })
+        .collect::<Vec<_>>();
+
+    bencher.iter(|| {
+        items
+            .iter()
+            .map(|item| get_priority(*item) as u32)
+            .sum::<u32>()
+    });
+}
+
+/// Generates groups of three rucksacks, each group sharing exactly one item.
+fn generate_input(groups: usize) -> String {
+    let letters = (b'a'..=b'z').chain(b'A'..=b'Z').collect::<Vec<_>>();
+    let mut rng = Rng::new(4);
+    let mut input = Vec::new();
+
+    for _ in 0..groups {
+        let badge = letters[rng.below(52) as usize];
+
+        for k in 0..3 {
+            // Every third letter is left out of each rucksack, so no other letter is in all three
+            let letters = letters
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| i % 3 != k)
+                .map(|(_, letter)| *letter)
+                .collect::<Vec<_>>();
+            let len = 2 * (8 + rng.below(8) as usize);
+
+            let mut rucksack = (0..len)
+                .map(|_| letters[rng.below(letters.len() as u64) as usize])
+                .collect::<Vec<_>>();
+            rucksack[rng.below(len as u64) as usize] = badge;
+
+            input.extend(rucksack);
+            input.push(b'\n');
+        }
+    }
+
+    String::from_utf8(input).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use std::{fs::File, io::BufReader};
@@ -119,6 +344,65 @@ mod tests {
     #[test]
     fn test_part_two() {
         let input = open_example();
-        assert_eq!(solve_part_two(input), 70);
+        assert_eq!(solve_part_two(input), Ok(70));
+    }
+
+    #[test]
+    fn test_group_sizes() {
+        let input = b"abcAB\nbcdAC\nbceAD\nbcfAE\n";
+
+        for find in [find_shared_item, find_shared_item_in_group] {
+            assert_eq!(sum_group_priorities(&input[..], 1, find), Ok(1 + 2 + 2 + 2));
+            assert_eq!(sum_group_priorities(&input[..], 2, find), Ok(2 + 2));
+            assert_eq!(sum_group_priorities(&input[..], 4, find), Ok(2));
+            assert_eq!(
+                sum_group_priorities(&input[..], 3, find),
+                Err(GroupError::Incomplete {
+                    first_line: 4,
+                    rucksacks: 1
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_no_shared_item() {
+        let input = b"abAB\nabAB\nabAB\nabAB\ncdCD\nefEF\n";
+
+        assert_eq!(
+            solve_part_two(&input[..]),
+            Err(GroupError::NoSharedItem { first_line: 4 })
+        );
+        assert_eq!(
+            solve_part_two_hash_set(&input[..]),
+            Err(GroupError::NoSharedItem { first_line: 4 })
+        );
+    }
+
+    #[test]
+    fn test_item_set_matches_hash_set() {
+        let input = generate_input(1_000);
+
+        assert_eq!(
+            solve_part_one(input.as_bytes()),
+            solve_part_one_hash_set(input.as_bytes())
+        );
+        assert_eq!(
+            solve_part_two(input.as_bytes()).unwrap(),
+            solve_part_two_hash_set(input.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_check_input() {
+        let example = std::fs::read("example.txt").unwrap();
+        assert!(aoc::check::check(&example, Some(check_input)).is_empty());
+
+        let violations = aoc::check::check(b"abCD\nab c\nabc\nabcd\n", Some(check_input));
+        let positions = violations
+            .iter()
+            .map(|violation| (violation.line, violation.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 3), (3, 3), (4, 1)]);
     }
 }
@@ -1,23 +1,22 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::io::BufRead;
 
+use aoc::{
+    check::{check_number, check_trailing_whitespace},
+    Day, Line, Part, Violation,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
 
 fn main() {
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
-
-    let answer_one = solve_part_one(reader);
-    println!("Answer 1: {}", answer_one);
-
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
-
-    let answer_two = solve_part_two(reader);
-    println!("Answer 2: {}", answer_two);
+    Day::new()
+        .solution(Part::One, "regex", |input| {
+            solve_part_one(input).to_string()
+        })
+        .solution(Part::Two, "regex", |input| {
+            solve_part_two(input).to_string()
+        })
+        .grammar(check_input)
+        .run();
 }
 
 fn solve_part_one<R: BufRead>(input: R) -> usize {
@@ -94,6 +93,53 @@ impl SectionAssignment {
     }
 }
 
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    lines.iter().filter_map(check_pair).collect()
+}
+
+fn check_pair(line: &Line) -> Option<Violation> {
+    if let Some(violation) = check_trailing_whitespace(line) {
+        return Some(violation);
+    }
+
+    let Some((a, b)) = line.text.split_once(',') else {
+        return Some(Violation::new(
+            line,
+            line.text.len() + 1,
+            "Expected two assignments separated by ','",
+            "write the pair as e.g. 2-4,6-8",
+        ));
+    };
+
+    check_assignment(line, 1, a).or_else(|| check_assignment(line, a.len() + 2, b))
+}
+
+fn check_assignment(line: &Line, column: usize, text: &str) -> Option<Violation> {
+    let Some((from, to)) = text.split_once('-') else {
+        return Some(Violation::new(
+            line,
+            column,
+            "Expected a section range",
+            "write the range as e.g. 2-4",
+        ));
+    };
+
+    check_number(line, column, from)
+        .or_else(|| check_number(line, column + from.len() + 1, to))
+        .or_else(|| {
+            if from.parse::<usize>().ok() > to.parse::<usize>().ok() {
+                Some(Violation::new(
+                    line,
+                    column,
+                    "Range starts after it ends",
+                    "swap the start and end sections",
+                ))
+            } else {
+                None
+            }
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use std::{fs::File, io::BufReader};
@@ -116,4 +162,17 @@ mod tests {
         let input = open_example();
         assert_eq!(solve_part_two(input), 4);
     }
+
+    #[test]
+    fn test_check_input() {
+        let example = std::fs::read("example.txt").unwrap();
+        assert!(aoc::check::check(&example, Some(check_input)).is_empty());
+
+        let violations = aoc::check::check(b"2-4;6-8\n2-4,6-x\n4-2,6-8\n", Some(check_input));
+        let positions = violations
+            .iter()
+            .map(|violation| (violation.line, violation.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(1, 8), (2, 7), (3, 1)]);
+    }
 }
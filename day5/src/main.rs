@@ -1,50 +1,57 @@
-use std::{
-    fs::File,
-    io::{self, BufRead, BufReader},
-};
+use std::io::BufRead;
 
+use aoc::{Day, Line, Part, Violation};
 use regex::Regex;
 
 type CrateStack = Vec<char>;
 type CraneMovement = (usize, usize, usize);
 
 fn main() {
-    let (mut stacks, movements) = load_input().unwrap();
+    Day::new()
+        .solution(Part::One, "crate mover 9000", |input| solve_part_one(input))
+        .solution(Part::Two, "crate mover 9001", |input| solve_part_two(input))
+        .grammar(check_input)
+        .run();
+}
 
-    let crate_mover_9001 = true;
+fn solve_part_one<R: BufRead>(input: R) -> String {
+    let (mut stacks, movements) = parse_input(input);
 
     for (move_count, from_stack_index, to_stack_index) in movements {
-        if crate_mover_9001 {
-            let stack_size = stacks[from_stack_index].len();
-            let mut popped = stacks[from_stack_index].split_off(stack_size - move_count);
-            stacks[to_stack_index].append(&mut popped);
-        } else {
-            for _ in 0..move_count {
-                let popped = stacks[from_stack_index].pop().unwrap();
-                stacks[to_stack_index].push(popped);
-            }
+        for _ in 0..move_count {
+            let popped = stacks[from_stack_index].pop().unwrap();
+            stacks[to_stack_index].push(popped);
         }
     }
 
-    println!("{:?}", stacks);
+    get_top_crates(&stacks)
+}
 
-    let top_crates = stacks.iter().map(|s| s.last().unwrap()).collect::<Vec<_>>();
-    let answer = String::from_iter(top_crates);
-    print!("{}", answer);
+fn solve_part_two<R: BufRead>(input: R) -> String {
+    let (mut stacks, movements) = parse_input(input);
+
+    for (move_count, from_stack_index, to_stack_index) in movements {
+        let stack_size = stacks[from_stack_index].len();
+        let mut popped = stacks[from_stack_index].split_off(stack_size - move_count);
+        stacks[to_stack_index].append(&mut popped);
+    }
+
+    get_top_crates(&stacks)
 }
 
-fn load_input() -> Result<(Vec<CrateStack>, Vec<CraneMovement>), io::Error> {
-    let file = File::open("input.txt")?;
-    let reader = BufReader::new(file);
+fn get_top_crates(stacks: &[CrateStack]) -> String {
+    stacks.iter().map(|s| s.last().unwrap()).collect()
+}
 
+fn parse_input<R: BufRead>(input: R) -> (Vec<CrateStack>, Vec<CraneMovement>) {
     let mut stacks = Vec::new();
     let mut movements = Vec::new();
     let movement_regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
 
-    for line in reader.lines() {
-        let line = line?;
+    for line in input.lines() {
+        let line = line.unwrap();
 
-        if line.starts_with('[') {
+        if line.contains('[') {
             load_stack_row(&line, &mut stacks)
         } else if line.starts_with('m') {
             let captures = movement_regex.captures(&line).unwrap();
@@ -60,7 +67,7 @@ fn load_input() -> Result<(Vec<CrateStack>, Vec<CraneMovement>), io::Error> {
         stack.reverse();
     }
 
-    Ok((stacks, movements))
+    (stacks, movements)
 }
 
 fn load_stack_row(row: &str, stacks: &mut Vec<Vec<char>>) {
@@ -84,3 +91,140 @@ fn load_stack_row(row: &str, stacks: &mut Vec<Vec<char>>) {
         stack_index += 1;
     }
 }
+
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    let movement_regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+    let mut violations = Vec::new();
+    let mut stack_count = 0;
+    let mut movement_count = 0;
+
+    for line in lines {
+        if line.text.contains('[') {
+            violations.extend(check_stack_row(line));
+            stack_count = stack_count.max((line.text.len() + 1) / 4);
+        } else if line.text.starts_with('m') {
+            let Some(captures) = movement_regex.captures(line.text) else {
+                violations.push(Violation::new(
+                    line,
+                    1,
+                    "Expected a movement",
+                    "write it as 'move N from A to B'",
+                ));
+                continue;
+            };
+
+            movement_count += 1;
+
+            for i in [2, 3] {
+                let stack = captures.get(i).unwrap();
+                let number = stack.as_str().parse::<usize>().unwrap_or(0);
+
+                if number == 0 || number > stack_count {
+                    violations.push(Violation::new(
+                        line,
+                        stack.start() + 1,
+                        "Movement refers to a stack that doesn't exist",
+                        "stacks are numbered from 1 to the number of columns in the drawing",
+                    ));
+                }
+            }
+        } else if !line.text.is_empty() && !line.text.trim_start().starts_with('1') {
+            violations.push(Violation::new(
+                line,
+                1,
+                "Line is neither part of the drawing nor a movement",
+                "remove the line",
+            ));
+        }
+    }
+
+    if movement_count == 0 {
+        if let Some(last) = lines.last() {
+            violations.push(Violation::new(
+                last,
+                1,
+                "Found no movements",
+                "add the movements after the drawing, the input may be cut short",
+            ));
+        }
+    }
+
+    violations
+}
+
+/// Stack rows are read as fixed 4 character columns, `[X] ` or four spaces, with the last
+/// separator left out.
+fn check_stack_row(line: &Line) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    if line.text.len() % 4 != 3 {
+        violations.push(Violation::new(
+            line,
+            line.text.len() + 1,
+            "Row doesn't end on a full column",
+            "pad the row with spaces so every column is 4 characters wide",
+        ));
+    }
+
+    for (i, cell) in line.text.as_bytes().chunks(4).enumerate() {
+        let column = i * 4 + 1;
+        let is_crate = cell.len() >= 3 && cell[0] == b'[' && cell[2] == b']';
+        let is_empty = cell.iter().take(3).all(|b| *b == b' ');
+
+        if !is_crate && !is_empty {
+            violations.push(Violation::new(
+                line,
+                column,
+                "Expected a crate or an empty column",
+                "write crates as [X] and empty spots as three spaces",
+            ));
+        } else if cell.len() == 4 && cell[3] != b' ' {
+            violations.push(Violation::new(
+                line,
+                column + 3,
+                "Expected a space between columns",
+                "separate the columns with a single space",
+            ));
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use super::*;
+
+    fn open_example() -> BufReader<File> {
+        let file = File::open("example.txt").unwrap();
+        BufReader::new(file)
+    }
+
+    #[test]
+    fn test_part_one() {
+        let input = open_example();
+        assert_eq!(solve_part_one(input), "CMZ");
+    }
+
+    #[test]
+    fn test_part_two() {
+        let input = open_example();
+        assert_eq!(solve_part_two(input), "MCD");
+    }
+
+    #[test]
+    fn test_check_input() {
+        let example = std::fs::read("example.txt").unwrap();
+        assert!(aoc::check::check(&example, Some(check_input)).is_empty());
+
+        let input = b"    [D]\n[N] [C] \n[Z] [M] [P]\n 1   2   3\n\nmove 1 from 4 to 1\n";
+        let violations = aoc::check::check(input, Some(check_input));
+        let positions = violations
+            .iter()
+            .map(|violation| (violation.line, violation.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 9), (6, 13)]);
+    }
+}
@@ -1,31 +1,47 @@
-use std::{
-    fs::File,
-    io::{BufRead, Read},
-};
+use std::io::BufRead;
 
-fn main() {
-    let mut input = String::new();
-    File::open("input.txt")
-        .unwrap()
-        .read_to_string(&mut input)
-        .unwrap();
-
-    let answer_one = solve_part_one(input.as_bytes());
-    println!("Answer 1: {}", answer_one);
+use aoc::{Bencher, Day, Line, Part, Violation};
 
-    let answer_two = solve_part_two(input.as_bytes());
-    println!("Answer 2: {}", answer_two);
+fn main() {
+    Day::new()
+        .solution(Part::One, "pairwise", |input| {
+            solve_part_one(input).to_string()
+        })
+        .solution(Part::One, "bitmask", |input| {
+            find_unique_sequence::<_, 4>(input, is_unique_sequence_bitmask).to_string()
+        })
+        .solution(Part::Two, "pairwise", |input| {
+            solve_part_two(input).to_string()
+        })
+        .solution(Part::Two, "bitmask", |input| {
+            find_unique_sequence::<_, 14>(input, is_unique_sequence_bitmask).to_string()
+        })
+        .grammar(check_input)
+        .benchmark(
+            "find_unique_sequence (pairwise)",
+            &[1_000, 10_000, 100_000],
+            |bencher, size| bench_find_unique_sequence(bencher, size, is_unique_sequence),
+        )
+        .benchmark(
+            "find_unique_sequence (bitmask)",
+            &[1_000, 10_000, 100_000],
+            |bencher, size| bench_find_unique_sequence(bencher, size, is_unique_sequence_bitmask),
+        )
+        .run();
 }
 
 fn solve_part_one<R: BufRead>(input: R) -> usize {
-    find_unique_sequence::<R, 4>(input)
+    find_unique_sequence::<R, 4>(input, is_unique_sequence)
 }
 
 fn solve_part_two<R: BufRead>(input: R) -> usize {
-    find_unique_sequence::<R, 14>(input)
+    find_unique_sequence::<R, 14>(input, is_unique_sequence)
 }
 
-fn find_unique_sequence<R: BufRead, const N: usize>(mut input: R) -> usize {
+fn find_unique_sequence<R: BufRead, const N: usize>(
+    mut input: R,
+    is_unique: fn(&[u8]) -> bool,
+) -> usize {
     let mut marker_buffer = [0; N];
     input.read_exact(&mut marker_buffer).unwrap();
 
@@ -33,7 +49,7 @@ fn find_unique_sequence<R: BufRead, const N: usize>(mut input: R) -> usize {
     let mut input_index = marker_buffer.len();
     let mut input_buffer: [u8; 1] = [0; 1];
 
-    while !is_unique_sequence(&marker_buffer) && input.read(&mut input_buffer).unwrap() != 0 {
+    while !is_unique(&marker_buffer) && input.read(&mut input_buffer).unwrap() != 0 {
         let character = input_buffer[0];
         marker_buffer[marker_buf_index] = character;
 
@@ -61,6 +77,69 @@ fn is_unique_sequence(marker: &[u8]) -> bool {
     true
 }
 
+/// Only tells ASCII bytes apart, which is all a datastream contains.
+fn is_unique_sequence_bitmask(marker: &[u8]) -> bool {
+    let mut seen = 0_u128;
+
+    for byte in marker {
+        let bit = 1_u128 << (byte & 0x7f);
+
+        if seen & bit != 0 {
+            return false;
+        }
+
+        seen |= bit;
+    }
+
+    true
+}
+
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for line in lines.iter().skip(1) {
+        violations.push(Violation::new(
+            line,
+            1,
+            "Datastream continues on another line",
+            "join the datastream into a single line",
+        ));
+    }
+
+    if let Some(line) = lines.first() {
+        if let Some(index) = line.text.find(|c: char| !c.is_ascii_lowercase()) {
+            violations.push(Violation::new(
+                line,
+                index + 1,
+                "Expected a lowercase letter",
+                "remove the character, the datastream only has a-z",
+            ));
+        } else if line.text.len() < 14 {
+            violations.push(Violation::new(
+                line,
+                line.text.len() + 1,
+                "Datastream is too short to contain a message marker",
+                "paste the whole datastream, the input may be cut short",
+            ));
+        }
+    }
+
+    violations
+}
+
+/// Measures finding a message marker in a datastream that only has one at the very end.
+fn bench_find_unique_sequence(bencher: &mut Bencher, size: usize, is_unique: fn(&[u8]) -> bool) {
+    let mut datastream = b"abcdefghijklm"
+        .iter()
+        .cycle()
+        .take(size.saturating_sub(14))
+        .copied()
+        .collect::<Vec<_>>();
+    datastream.extend_from_slice(b"nopqrstuvwxyza");
+
+    bencher.iter(|| find_unique_sequence::<_, 14>(datastream.as_slice(), is_unique));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,4 +176,34 @@ mod tests {
             assert_eq!(solve_part_two(input.as_bytes()), expected_result);
         }
     }
+
+    #[test]
+    fn test_check_input() {
+        for (input, _) in get_example_two() {
+            let input = format!("{}\n", input);
+            assert!(aoc::check::check(input.as_bytes(), Some(check_input)).is_empty());
+        }
+
+        let violations =
+            aoc::check::check(b"mjqjpq mgbljsphdztnvjfq\nwrcgsmlb\n", Some(check_input));
+        let positions = violations
+            .iter()
+            .map(|violation| (violation.line, violation.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(1, 7), (2, 1)]);
+    }
+
+    #[test]
+    fn test_bitmask() {
+        for (input, expected_result) in get_example_one() {
+            let result = find_unique_sequence::<_, 4>(input.as_bytes(), is_unique_sequence_bitmask);
+            assert_eq!(result, expected_result);
+        }
+
+        for (input, expected_result) in get_example_two() {
+            let result =
+                find_unique_sequence::<_, 14>(input.as_bytes(), is_unique_sequence_bitmask);
+            assert_eq!(result, expected_result);
+        }
+    }
 }
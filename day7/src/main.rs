@@ -1,20 +1,25 @@
-use std::{
-    fs::File,
-    io::{BufRead, Read},
+use std::io::BufRead;
+
+use aoc::{
+    check::{check_number, check_trailing_whitespace},
+    Bencher, Day, Line, Part, Violation,
 };
 
 fn main() {
-    let mut input = String::new();
-    File::open("input.txt")
-        .unwrap()
-        .read_to_string(&mut input)
-        .unwrap();
-
-    let answer_one = solve_part_one(input.as_bytes());
-    println!("Answer 1: {}", answer_one);
-
-    let answer_two = solve_part_two(input.as_bytes());
-    println!("Answer 2: {}", answer_two);
+    Day::new()
+        .solution(Part::One, "parent index", |input| {
+            solve_part_one(input).to_string()
+        })
+        .solution(Part::Two, "parent index", |input| {
+            solve_part_two(input).to_string()
+        })
+        .grammar(check_input)
+        .benchmark(
+            "update_parent_sizes",
+            &[10, 100, 1_000, 10_000],
+            bench_update_parent_sizes,
+        )
+        .run();
 }
 
 fn solve_part_one<R: BufRead>(input: R) -> usize {
@@ -107,8 +112,86 @@ fn update_parent_sizes(directories: &mut [Dir], dir_index: usize, file_size: usi
     }
 }
 
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    if let Some(first) = lines.first() {
+        if first.text != "$ cd /" {
+            violations.push(Violation::new(
+                first,
+                1,
+                "Terminal output doesn't start in the root directory",
+                "start the input with '$ cd /'",
+            ));
+        }
+    }
+
+    for line in lines {
+        let violation = if let Some(command) = line.text.strip_prefix("$ ") {
+            check_command(line, command)
+        } else if let Some(name) = line.text.strip_prefix("dir ") {
+            check_name(line, 5, name)
+        } else if let Some((size, name)) = line.text.split_once(' ') {
+            check_number(line, 1, size).or_else(|| check_name(line, size.len() + 2, name))
+        } else {
+            Some(Violation::new(
+                line,
+                1,
+                "Expected a command, a directory or a file",
+                "remove the line, or fix it to look like '$ ls', 'dir a' or '123 a.txt'",
+            ))
+        };
+
+        violations.extend(violation.or_else(|| check_trailing_whitespace(line)));
+    }
+
+    violations
+}
+
+fn check_command(line: &Line, command: &str) -> Option<Violation> {
+    if command == "ls" {
+        None
+    } else if let Some(name) = command.strip_prefix("cd ") {
+        check_name(line, 6, name)
+    } else {
+        Some(Violation::new(
+            line,
+            3,
+            "Unknown command",
+            "the only commands are 'cd' and 'ls'",
+        ))
+    }
+}
+
+fn check_name(line: &Line, column: usize, name: &str) -> Option<Violation> {
+    if name.trim().is_empty() {
+        Some(Violation::new(
+            line,
+            column,
+            "Expected a name",
+            "add the missing name",
+        ))
+    } else {
+        None
+    }
+}
+
+/// Measures adding a file at the bottom of a chain of nested directories, `size` deep.
+fn bench_update_parent_sizes(bencher: &mut Bencher, size: usize) {
+    let mut dirs = (0..size)
+        .map(|i| Dir {
+            name: i.to_string(),
+            size: 0,
+            parent_index: i.saturating_sub(1),
+        })
+        .collect::<Vec<_>>();
+
+    bencher.iter(|| update_parent_sizes(&mut dirs, size - 1, 1));
+}
+
 #[derive(Debug)]
 struct Dir {
+    #[allow(dead_code)] // Only read through the Debug output
     name: String,
     size: usize,
     parent_index: usize,
@@ -116,7 +199,7 @@ struct Dir {
 
 #[cfg(test)]
 mod tests {
-    use std::io::BufReader;
+    use std::{fs::File, io::BufReader};
 
     use super::*;
 
@@ -133,4 +216,18 @@ mod tests {
     fn test_part_two() {
         assert_eq!(solve_part_two(get_example()), 24933642);
     }
+
+    #[test]
+    fn test_check_input() {
+        let example = std::fs::read("example.txt").unwrap();
+        assert!(aoc::check::check(&example, Some(check_input)).is_empty());
+
+        let input = b"$ cd /\n$ dir\n12a b.txt\n$ cd \nsomething\n";
+        let violations = aoc::check::check(input, Some(check_input));
+        let positions = violations
+            .iter()
+            .map(|violation| (violation.line, violation.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 3), (3, 3), (4, 6), (5, 1)]);
+    }
 }
@@ -1,21 +1,25 @@
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::{collections::HashSet, io::BufRead};
 
-fn main() {
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
-
-    let answer_one = solve_part_one(reader);
-    println!("Answer 1: {}", answer_one);
+use aoc::{check::check_number, Bencher, Day, Line, Part, Violation};
 
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
-
-    let answer_two = solve_part_two(reader);
-    println!("Answer 2: {}", answer_two);
+fn main() {
+    Day::new()
+        .solution(Part::One, "stack", |input| {
+            solve_part_one(input).to_string()
+        })
+        .solution(Part::One, "brute force", |input| {
+            solve_part_one_brute_force(input).to_string()
+        })
+        .solution(Part::Two, "view distance", |input| {
+            solve_part_two(input).to_string()
+        })
+        .grammar(check_input)
+        .benchmark(
+            "find_view_distance_forward",
+            &[100, 1_000, 10_000, 100_000],
+            bench_find_view_distance_forward,
+        )
+        .run();
 }
 
 fn solve_part_one<R: BufRead>(input: R) -> usize {
@@ -74,6 +78,32 @@ fn solve_part_one<R: BufRead>(input: R) -> usize {
     visible.len()
 }
 
+fn solve_part_one_brute_force<R: BufRead>(input: R) -> usize {
+    let rows = input
+        .lines()
+        .map(|line| line.unwrap().into_bytes())
+        .collect::<Vec<_>>();
+
+    let mut visible_count = 0;
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, height) in row.iter().enumerate() {
+            let is_taller = |other: &u8| *other < *height;
+
+            let visible = row[..x].iter().all(is_taller)
+                || row[(x + 1)..].iter().all(is_taller)
+                || rows[..y].iter().map(|r| &r[x]).all(is_taller)
+                || rows[(y + 1)..].iter().map(|r| &r[x]).all(is_taller);
+
+            if visible {
+                visible_count += 1;
+            }
+        }
+    }
+
+    visible_count
+}
+
 fn solve_part_two<R: BufRead>(input: R) -> usize {
     let mut rows = Vec::new();
     let mut cols = Vec::new();
@@ -143,6 +173,34 @@ fn find_view_distance_backward(trees: &[u8], position: usize) -> usize {
     }
 }
 
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    let width = lines.first().map_or(0, |line| line.text.len());
+    let mut violations = Vec::new();
+
+    for line in lines {
+        if let Some(violation) = check_number(line, 1, line.text) {
+            violations.push(violation);
+        } else if line.text.len() != width {
+            violations.push(Violation::new(
+                line,
+                line.text.len().min(width) + 1,
+                "Row is not as wide as the first row",
+                "make every row of the grid the same width",
+            ));
+        }
+    }
+
+    violations
+}
+
+/// Measures the worst case, a tree that sees over every other tree in the row.
+fn bench_find_view_distance_forward(bencher: &mut Bencher, size: usize) {
+    let mut trees = vec![b'0'; size];
+    trees[0] = b'9';
+
+    bencher.iter(|| find_view_distance_forward(&trees, 0));
+}
+
 #[cfg(test)]
 mod tests {
     use std::{fs::File, io::BufReader};
@@ -160,6 +218,25 @@ mod tests {
         assert_eq!(solve_part_one(input), 21);
     }
 
+    #[test]
+    fn test_check_input() {
+        let example = std::fs::read("example.txt").unwrap();
+        assert!(aoc::check::check(&example, Some(check_input)).is_empty());
+
+        let violations = aoc::check::check(b"303\n2551\n6 3\n", Some(check_input));
+        let positions = violations
+            .iter()
+            .map(|violation| (violation.line, violation.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 4), (3, 2)]);
+    }
+
+    #[test]
+    fn test_part_one_brute_force() {
+        let input = open_example();
+        assert_eq!(solve_part_one_brute_force(input), 21);
+    }
+
     #[test]
     fn test_part_two() {
         let input = open_example();
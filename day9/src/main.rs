@@ -1,23 +1,30 @@
 use std::{
-    cmp::{max, min},
     collections::HashSet,
     fmt::{self, Display},
-    fs::File,
-    io::{BufRead, BufReader},
+    io::BufRead,
 };
 
-fn main() {
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
-
-    let answer_one = solve_part_one(reader);
-    println!("Answer 1: {}", answer_one);
-
-    let input = File::open("input.txt").unwrap();
-    let reader = BufReader::new(input);
+use aoc::{
+    check::{check_number, check_trailing_whitespace},
+    rng::Rng,
+    Bencher, Day, Line, Part, Violation,
+};
 
-    let answer_two = solve_part_two(reader);
-    println!("Answer 2: {}", answer_two);
+fn main() {
+    Day::new()
+        .solution(Part::One, "knots", |input| {
+            solve_part_one(input).to_string()
+        })
+        .solution(Part::Two, "knots", |input| {
+            solve_part_two(input).to_string()
+        })
+        .grammar(check_input)
+        .benchmark(
+            "find_movement_to_next_knot",
+            &[1_000, 10_000, 100_000],
+            bench_find_movement_to_next_knot,
+        )
+        .run();
 }
 
 fn solve_part_one<R: BufRead>(input: R) -> usize {
@@ -81,12 +88,66 @@ fn find_movement_to_next_knot(current: &Position, next: &Position) -> (i32, i32)
     let distance = next.distance_from(current);
 
     if distance.0.abs() > 1 || distance.1.abs() > 1 {
-        (max(min(distance.0, 1), -1), max(min(distance.1, 1), -1))
+        (distance.0.clamp(-1, 1), distance.1.clamp(-1, 1))
     } else {
         (0, 0)
     }
 }
 
+fn check_input(lines: &[Line]) -> Vec<Violation> {
+    lines
+        .iter()
+        .filter_map(|line| {
+            let Some((direction, steps)) = line.text.split_once(' ') else {
+                return Some(Violation::new(
+                    line,
+                    1,
+                    "Expected a direction and a number of steps",
+                    "write the motion as e.g. 'R 4'",
+                ));
+            };
+
+            if !["R", "L", "U", "D"].contains(&direction) {
+                Some(Violation::new(
+                    line,
+                    1,
+                    "Unknown direction",
+                    "use R, L, U or D",
+                ))
+            } else {
+                check_trailing_whitespace(line)
+                    .or_else(|| check_number(line, direction.len() + 2, steps))
+            }
+        })
+        .collect()
+}
+
+/// Measures following `size` random pairs of knots that are at most two steps apart.
+fn bench_find_movement_to_next_knot(bencher: &mut Bencher, size: usize) {
+    let mut rng = Rng::new(9);
+    let mut random_offset = || rng.below(5) as i32 - 2;
+
+    let knots = (0..size)
+        .map(|_| {
+            let current = Position::zero();
+            let next = Position {
+                x: random_offset(),
+                y: random_offset(),
+            };
+            (current, next)
+        })
+        .collect::<Vec<_>>();
+
+    bencher.iter(|| {
+        knots
+            .iter()
+            .map(|(current, next)| find_movement_to_next_knot(current, next))
+            .fold((0, 0), |total, movement| {
+                (total.0 + movement.0, total.1 + movement.1)
+            })
+    });
+}
+
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 struct Position {
     x: i32,
@@ -147,4 +208,17 @@ mod tests {
         let input = open_example_two();
         assert_eq!(solve_part_two(input), 36);
     }
+
+    #[test]
+    fn test_check_input() {
+        let example = std::fs::read("example1.txt").unwrap();
+        assert!(aoc::check::check(&example, Some(check_input)).is_empty());
+
+        let violations = aoc::check::check(b"R 4\nX 2\nU 1 \nL\nD 1x\n", Some(check_input));
+        let positions = violations
+            .iter()
+            .map(|violation| (violation.line, violation.column))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(2, 1), (3, 4), (4, 1), (5, 4)]);
+    }
 }